@@ -119,8 +119,10 @@
 use core::convert::Infallible;
 use core::marker::PhantomData;
 use embedded_hal::adc::{Channel, OneShot};
+use fugit::HertzU32;
 
 use crate::{
+    clocks::{AdcClock, Clock},
     dma,
     gpio::{
         bank0::{Gpio26, Gpio27, Gpio28, Gpio29},
@@ -132,6 +134,9 @@ use crate::{
 
 const TEMPERATURE_SENSOR_CHANNEL: u8 = 4;
 
+/// Number of ADC clock cycles needed for one conversion.
+const CYCLES_PER_CONVERSION: u32 = 96;
+
 /// The pin was invalid for the requested operation
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -284,6 +289,32 @@ impl Adc {
         self.device.cs.modify(|_, w| w.ts_en().clear_bit());
     }
 
+    /// Manually set clock divider to control the free-running sample rate
+    ///
+    /// See [`AdcFifoBuilder::clock_divider`] for how `int` and `frac` relate to the sample rate.
+    /// The divider only paces free-running mode and is reset by [`AdcFifo::stop`].
+    pub fn set_clkdiv(&mut self, int: u16, frac: u8) {
+        self.device
+            .div
+            .modify(|_, w| unsafe { w.int().bits(int).frac().bits(frac) });
+    }
+
+    /// Set the clock divider to get as close as possible to `rate` in free-running mode
+    ///
+    /// The divider is computed from the frequency of the `adc_clock`, normally 48MHz.
+    /// Each conversion takes 96 ADC clock cycles, so the fastest rate is `48MHz / 96 = 500ksps`
+    /// and requesting anything above that selects the fastest rate. The slowest rate is
+    /// `48MHz / 65536 = 732.41Hz`.
+    ///
+    /// Returns the sample rate that is actually achieved.
+    ///
+    /// The divider only paces free-running mode and is reset by [`AdcFifo::stop`].
+    pub fn set_sample_rate(&mut self, rate: HertzU32, adc_clock: &AdcClock) -> HertzU32 {
+        let (int, frac, achieved) = calc_clock_divider(adc_clock.freq().to_Hz(), rate.to_Hz());
+        self.set_clkdiv(int, frac);
+        HertzU32::from_raw(achieved)
+    }
+
     /// Start configuring free-running mode, and set up the FIFO
     ///
     /// The [`AdcFifoBuilder`] returned by this method can be used
@@ -355,6 +386,24 @@ where
     }
 }
 
+/// Computes the clock divider closest to `rate` from an ADC clock running at `adc_freq`.
+///
+/// Returns the integer and fractional parts of the divider, and the achieved sample rate.
+fn calc_clock_divider(adc_freq: u32, rate: u32) -> (u16, u8, u32) {
+    let rate = rate.max(1);
+    if rate >= adc_freq / CYCLES_PER_CONVERSION {
+        return (0, 0, adc_freq / CYCLES_PER_CONVERSION);
+    }
+
+    // The sample period is (1 + int + frac / 256) ADC clock cycles.
+    let period = (u64::from(adc_freq) * 256 + u64::from(rate) / 2) / u64::from(rate);
+    let div = (period - 256).min(0xFF_FFFF);
+    let (int, frac) = ((div >> 8) as u16, (div & 0xFF) as u8);
+    let achieved = u64::from(adc_freq) * 256 / (div + 256);
+
+    (int, frac, achieved as u32)
+}
+
 /// Used to configure & build an [`AdcFifo`]
 ///
 /// See [`Adc::build_fifo`] for details, as well as the `adc_fifo_*` [examples](https://github.com/rp-rs/rp-hal/tree/main/rp2040-hal/examples).
@@ -795,3 +844,29 @@ where
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calc_clock_divider() {
+        const FREQ: u32 = 48_000_000;
+
+        // values from the `AdcFifoBuilder::clock_divider` documentation
+        assert_eq!(calc_clock_divider(FREQ, 1000), (47999, 0, 1000));
+        assert_eq!(calc_clock_divider(FREQ, 1024), (46874, 0, 1024));
+        assert_eq!(calc_clock_divider(FREQ, 1337), (35900, 70, 1336));
+        assert_eq!(calc_clock_divider(FREQ, 4096), (11717, 192, 4096));
+        assert_eq!(calc_clock_divider(FREQ, 96_000), (499, 0, 96_000));
+
+        // conversion time limits the fastest rate
+        assert_eq!(calc_clock_divider(FREQ, 500_000), (0, 0, 500_000));
+        assert_eq!(calc_clock_divider(FREQ, 1_000_000), (0, 0, 500_000));
+
+        // the divider limits the slowest rate
+        assert_eq!(calc_clock_divider(FREQ, 733), (65483, 80, 732));
+        assert_eq!(calc_clock_divider(FREQ, 100), (0xFFFF, 0xFF, 732));
+        assert_eq!(calc_clock_divider(FREQ, 0), (0xFFFF, 0xFF, 732));
+    }
+}
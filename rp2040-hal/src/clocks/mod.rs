@@ -308,6 +308,7 @@ impl ClocksManager {
 }
 
 /// Possible init errors
+#[derive(Debug)]
 pub enum InitError {
     /// Something went wrong setting up the Xosc
     XoscErr(XoscError),
@@ -318,6 +319,10 @@ pub enum InitError {
 }
 
 /// Initialize the clocks and plls according to the reference implementation
+///
+/// This brings up the XOSC, PLL_SYS at 125MHz and PLL_USB at 48MHz, starts the watchdog tick
+/// at 1MHz and configures all the clocks. If the crystal never becomes stable or a PLL never
+/// locks, an error is returned instead of hanging.
 pub fn init_clocks_and_plls(
     xosc_crystal_freq: u32,
     xosc_dev: XOSC,
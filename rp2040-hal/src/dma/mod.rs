@@ -285,6 +285,94 @@ pub enum Pace {
     // TODO: Timers?
 }
 
+/// Calculation performed by the DMA sniffer on the data sniffed from a channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SniffCalc {
+    /// CRC-32 (IEEE 802.3 polynomial 0x04C11DB7).
+    Crc32,
+    /// CRC-32 (IEEE 802.3 polynomial) with bit reversed data.
+    Crc32Reversed,
+    /// CRC-16-CCITT (polynomial 0x1021).
+    Crc16Ccitt,
+    /// CRC-16-CCITT (polynomial 0x1021) with bit reversed data.
+    Crc16CcittReversed,
+    /// XOR reduction over all data: the result is 1 if the total number of set bits is odd.
+    Parity,
+    /// Simple 32 bit checksum (sum of all data words).
+    Sum,
+}
+
+/// Configuration of the DMA sniffer.
+///
+/// The sniffer watches the data going through a single DMA channel and accumulates a checksum
+/// over it, without any extra pass over the data. There is only one sniffer shared by all
+/// channels.
+///
+/// The standard CRC-32 (as used by Ethernet or zlib) is obtained with
+/// [`SniffCalc::Crc32Reversed`], a seed of `0xFFFF_FFFF` and both `out_reverse` and `out_invert`
+/// set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SniffConfig {
+    /// The calculation to perform.
+    pub calc: SniffCalc,
+    /// Initial value of the accumulator.
+    pub seed: u32,
+    /// Byte swap the data before feeding it to the calculation.
+    ///
+    /// This is applied on top of the channel's own byte swapping, so setting both makes the
+    /// sniffer see the data in memory order.
+    pub bswap: bool,
+    /// Bit reverse the result when it is read.
+    pub out_reverse: bool,
+    /// Bit invert the result when it is read.
+    pub out_invert: bool,
+}
+
+impl SniffConfig {
+    /// Creates a new sniffer configuration with a seed of zero and no data or result
+    /// manipulation.
+    pub fn new(calc: SniffCalc) -> Self {
+        Self {
+            calc,
+            seed: 0,
+            bswap: false,
+            out_reverse: false,
+            out_invert: false,
+        }
+    }
+}
+
+/// Enables the sniffer on channel `id` and loads the seed.
+fn enable_sniffer(id: u8, config: &SniffConfig) {
+    // Safety: The sniffer registers are only accessed by transfers using the sniffer, which the
+    // user must not run concurrently (see `SniffConfig`).
+    let dma = unsafe { &*pac::DMA::ptr() };
+    dma.sniff_data.write(|w| unsafe { w.bits(config.seed) });
+    dma.sniff_ctrl.write(|w| {
+        unsafe { w.dmach().bits(id) };
+        match config.calc {
+            SniffCalc::Crc32 => w.calc().crc32(),
+            SniffCalc::Crc32Reversed => w.calc().crc32r(),
+            SniffCalc::Crc16Ccitt => w.calc().crc16(),
+            SniffCalc::Crc16CcittReversed => w.calc().crc16r(),
+            SniffCalc::Parity => w.calc().even(),
+            SniffCalc::Sum => w.calc().sum(),
+        };
+        w.bswap().bit(config.bswap);
+        w.out_rev().bit(config.out_reverse);
+        w.out_inv().bit(config.out_invert);
+        w.en().set_bit()
+    });
+}
+
+/// Reads the sniffer result.
+fn sniffer_result() -> u32 {
+    // Safety: Read-only access.
+    unsafe { &*pac::DMA::ptr() }.sniff_data.read().bits()
+}
+
 /// Error during DMA configuration.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
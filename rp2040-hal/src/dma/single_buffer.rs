@@ -3,7 +3,8 @@
 use core::sync::atomic::{compiler_fence, Ordering};
 
 use super::{
-    single_channel::ChannelConfig, single_channel::SingleChannel, Pace, ReadTarget, WriteTarget,
+    enable_sniffer, single_channel::ChannelConfig, single_channel::SingleChannel, sniffer_result,
+    Pace, ReadTarget, SniffConfig, WriteTarget,
 };
 
 /// Configuration for single-buffered DMA transfer
@@ -13,6 +14,7 @@ pub struct Config<CH: SingleChannel, FROM: ReadTarget, TO: WriteTarget> {
     to: TO,
     pace: Pace,
     bswap: bool,
+    sniff: Option<SniffConfig>,
 }
 
 impl<CH, FROM, TO, WORD> Config<CH, FROM, TO>
@@ -29,6 +31,7 @@ where
             to,
             pace: Pace::PreferSource,
             bswap: false,
+            sniff: None,
         }
     }
 
@@ -52,6 +55,15 @@ where
         self.bswap = bswap;
     }
 
+    /// Enable the sniffer on this transfer, default is disabled.
+    ///
+    /// The sniffer accumulates a checksum over all the data transferred, which can be read with
+    /// [`Transfer::sniff_result`]. As there is only a single sniffer, at most one transfer may
+    /// use it at any time.
+    pub fn sniff(&mut self, sniff: SniffConfig) {
+        self.sniff = Some(sniff);
+    }
+
     /// Start the DMA transfer
     pub fn start(mut self) -> Transfer<CH, FROM, TO> {
         // TODO: Do we want to call any callbacks to configure source/sink?
@@ -62,8 +74,19 @@ where
         compiler_fence(Ordering::SeqCst);
 
         // Configure the DMA channel and start it.
-        self.ch
-            .config(&self.from, &mut self.to, self.pace, self.bswap, None, true);
+        if let Some(sniff) = self.sniff {
+            self.ch
+                .config(&self.from, &mut self.to, self.pace, self.bswap, None, false);
+            enable_sniffer(self.ch.id(), &sniff);
+            self.ch
+                .ch()
+                .ch_al1_ctrl
+                .modify(|_, w| w.sniff_en().set_bit());
+            self.ch.start();
+        } else {
+            self.ch
+                .config(&self.from, &mut self.to, self.pace, self.bswap, None, true);
+        }
 
         Transfer {
             ch: self.ch,
@@ -102,6 +125,13 @@ where
         !self.ch.ch().ch_ctrl_trig.read().busy().bit_is_set()
     }
 
+    /// Read the checksum accumulated by the sniffer so far.
+    ///
+    /// This is only meaningful if the sniffer was enabled with [`Config::sniff`].
+    pub fn sniff_result(&self) -> u32 {
+        sniffer_result()
+    }
+
     /// Block until the transfer is complete, returning the channel and targets
     pub fn wait(self) -> (CH, FROM, TO) {
         while !self.is_done() {}
@@ -0,0 +1,268 @@
+//! Async GPIO input futures
+//!
+//! Input pins can be awaited until they reach a level or see an edge. Each future enables the
+//! matching GPIO interrupt while it is pending and disables it again once it has resolved or is
+//! dropped.
+//!
+//! This requires the IO_IRQ_BANK0 (and IO_IRQ_QSPI if QSPI pins are awaited) interrupt to be
+//! unmasked in the NVIC and its handler to call [`on_interrupt`].
+//!
+//! ```ignore
+//! # use rp2040_hal::{gpio::Pins, pac, Sio};
+//! use rp2040_hal::pac::interrupt;
+//!
+//! #[interrupt]
+//! fn IO_IRQ_BANK0() {
+//!     rp2040_hal::gpio::asynch::on_interrupt();
+//! }
+//!
+//! async fn wait_for_button() {
+//!     let mut pac = pac::Peripherals::take().unwrap();
+//!     let sio = Sio::new(pac.SIO);
+//!     let pins = Pins::new(pac.IO_BANK0, pac.PADS_BANK0, sio.gpio_bank0, &mut pac.RESETS);
+//!     let mut button = pins.gpio23.into_pull_up_input();
+//!     unsafe { pac::NVIC::unmask(pac::Interrupt::IO_IRQ_BANK0) };
+//!
+//!     button.wait_for_falling_edge().await;
+//! }
+//! ```
+
+use core::{
+    cell::RefCell,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin as CorePin,
+    task::{Context, Poll, Waker},
+};
+
+use critical_section::Mutex;
+
+use super::{
+    pin::pin_sealed::PinIdOps, DynBankId, DynPinId, FunctionSio, Interrupt, Pin, PinId, PullType,
+    SioInput,
+};
+use crate::{
+    atomic_register_access::{write_bitmask_clear, write_bitmask_set},
+    sio::{CoreId, Sio},
+};
+
+const BANK0_PINS: usize = 30;
+const QSPI_PINS: usize = 6;
+
+/// Waiter state for a single pin.
+struct Slot {
+    waker: Option<Waker>,
+    /// Interrupt bits enabled on behalf of the pending future.
+    armed: u32,
+    /// Core whose interrupt enable register has been armed.
+    core: CoreId,
+    /// Set by the interrupt handler when one of the armed interrupts fired.
+    fired: bool,
+}
+
+impl Slot {
+    const fn new() -> Self {
+        Self {
+            waker: None,
+            armed: 0,
+            core: CoreId::Core0,
+            fired: false,
+        }
+    }
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const EMPTY_SLOT: Mutex<RefCell<Slot>> = Mutex::new(RefCell::new(Slot::new()));
+static SLOTS: [Mutex<RefCell<Slot>>; BANK0_PINS + QSPI_PINS] = [EMPTY_SLOT; BANK0_PINS + QSPI_PINS];
+
+fn slot_index(id: DynPinId) -> usize {
+    match id.bank {
+        DynBankId::Bank0 => usize::from(id.num),
+        DynBankId::Qspi => BANK0_PINS + usize::from(id.num),
+    }
+}
+
+fn set_enabled(id: DynPinId, core: CoreId, mask: u32, enabled: bool) {
+    let (reg, offset) = id.proc_inte(core);
+    unsafe {
+        if enabled {
+            write_bitmask_set(reg.as_ptr(), mask << offset);
+        } else {
+            write_bitmask_clear(reg.as_ptr(), mask << offset);
+        }
+    }
+}
+
+/// Wakes the futures whose pin interrupt fired.
+///
+/// This must be called from the IO_IRQ_BANK0 and/or IO_IRQ_QSPI interrupt handlers. Interrupts
+/// that were not enabled by a future are left untouched, so this can be combined with other
+/// interrupt handling code.
+pub fn on_interrupt() {
+    let core = Sio::core();
+    let bank0 = (0..BANK0_PINS as u8).map(|num| DynPinId {
+        bank: DynBankId::Bank0,
+        num,
+    });
+    let qspi = (0..QSPI_PINS as u8).map(|num| DynPinId {
+        bank: DynBankId::Qspi,
+        num,
+    });
+
+    critical_section::with(|cs| {
+        for id in bank0.chain(qspi) {
+            let mut slot = SLOTS[slot_index(id)].borrow_ref_mut(cs);
+            if slot.armed == 0 || slot.core != core {
+                continue;
+            }
+
+            let (reg, offset) = id.proc_ints(core);
+            if (reg.read().bits() >> offset) & slot.armed == 0 {
+                continue;
+            }
+
+            set_enabled(id, core, slot.armed, false);
+            slot.armed = 0;
+            slot.fired = true;
+            if let Some(waker) = slot.waker.take() {
+                waker.wake();
+            }
+        }
+    });
+}
+
+#[derive(Clone, Copy)]
+enum Wait {
+    High,
+    Low,
+    RisingEdge,
+    FallingEdge,
+    AnyEdge,
+}
+
+impl Wait {
+    fn mask(self) -> u32 {
+        match self {
+            Wait::High => Interrupt::LevelHigh.mask(),
+            Wait::Low => Interrupt::LevelLow.mask(),
+            Wait::RisingEdge => Interrupt::EdgeHigh.mask(),
+            Wait::FallingEdge => Interrupt::EdgeLow.mask(),
+            Wait::AnyEdge => Interrupt::EdgeHigh.mask() | Interrupt::EdgeLow.mask(),
+        }
+    }
+
+    /// Whether the condition is already met, for level waits.
+    fn level_reached(self, id: DynPinId) -> bool {
+        let high = id.sio_in().read().bits() & id.mask() != 0;
+        match self {
+            Wait::High => high,
+            Wait::Low => !high,
+            _ => false,
+        }
+    }
+}
+
+/// Future returned by the `wait_for_*` methods of input pins.
+///
+/// Dropping it disables the pin interrupt it enabled.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct InputFuture<'p> {
+    id: DynPinId,
+    wait: Wait,
+    _pin: PhantomData<&'p mut ()>,
+}
+
+impl<'p> InputFuture<'p> {
+    fn new(id: DynPinId, wait: Wait) -> Self {
+        Self {
+            id,
+            wait,
+            _pin: PhantomData,
+        }
+    }
+}
+
+impl<'p> Future for InputFuture<'p> {
+    type Output = ();
+
+    fn poll(self: CorePin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let id = self.id;
+        let wait = self.wait;
+
+        critical_section::with(|cs| {
+            let mut slot = SLOTS[slot_index(id)].borrow_ref_mut(cs);
+
+            if slot.fired {
+                slot.fired = false;
+                // A level may already be gone again by the time we are polled, in which case we
+                // simply wait for it again.
+                if matches!(wait, Wait::RisingEdge | Wait::FallingEdge | Wait::AnyEdge)
+                    || wait.level_reached(id)
+                {
+                    return Poll::Ready(());
+                }
+            } else if slot.armed == 0 && wait.level_reached(id) {
+                return Poll::Ready(());
+            }
+
+            if slot.armed == 0 {
+                let core = Sio::core();
+                let mask = wait.mask();
+
+                // Discard edges latched before this future was polled.
+                let (reg, offset) = id.intr();
+                reg.write(|w| unsafe { w.bits(mask << offset) });
+
+                slot.armed = mask;
+                slot.core = core;
+                set_enabled(id, core, mask, true);
+            }
+
+            slot.waker = Some(cx.waker().clone());
+            Poll::Pending
+        })
+    }
+}
+
+impl<'p> Drop for InputFuture<'p> {
+    fn drop(&mut self) {
+        critical_section::with(|cs| {
+            let mut slot = SLOTS[slot_index(self.id)].borrow_ref_mut(cs);
+            if slot.armed != 0 {
+                set_enabled(self.id, slot.core, slot.armed, false);
+            }
+            *slot = Slot::new();
+        });
+    }
+}
+
+impl<I: PinId, P: PullType> Pin<I, FunctionSio<SioInput>, P> {
+    /// Wait until the pin is high.
+    ///
+    /// Resolves immediately if the pin is already high.
+    pub fn wait_for_high(&mut self) -> InputFuture<'_> {
+        InputFuture::new(self.id.as_dyn(), Wait::High)
+    }
+
+    /// Wait until the pin is low.
+    ///
+    /// Resolves immediately if the pin is already low.
+    pub fn wait_for_low(&mut self) -> InputFuture<'_> {
+        InputFuture::new(self.id.as_dyn(), Wait::Low)
+    }
+
+    /// Wait for the next rising edge on the pin.
+    pub fn wait_for_rising_edge(&mut self) -> InputFuture<'_> {
+        InputFuture::new(self.id.as_dyn(), Wait::RisingEdge)
+    }
+
+    /// Wait for the next falling edge on the pin.
+    pub fn wait_for_falling_edge(&mut self) -> InputFuture<'_> {
+        InputFuture::new(self.id.as_dyn(), Wait::FallingEdge)
+    }
+
+    /// Wait for the next rising or falling edge on the pin.
+    pub fn wait_for_any_edge(&mut self) -> InputFuture<'_> {
+        InputFuture::new(self.id.as_dyn(), Wait::AnyEdge)
+    }
+}
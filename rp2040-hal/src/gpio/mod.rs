@@ -48,6 +48,8 @@ use crate::{
     typelevel::{self, Sealed},
 };
 
+#[cfg(feature = "async")]
+pub mod asynch;
 mod func;
 pub(crate) mod pin;
 mod pin_group;
@@ -755,6 +757,14 @@ impl<I: PinId, C: SioConfig, P: PullType> Pin<I, FunctionSio<C>, P> {
     /// Bypass the input sync stages.
     ///
     /// This saves two clock cycles in the input signal's path at the risks of intruducing metastability.
+    ///
+    /// Only the processors' (SIO) view of the input is affected. PIO has its own synchronizers,
+    /// see [`PIO::set_input_sync_bypass`](crate::pio::PIO::set_input_sync_bypass).
+    ///
+    /// **Warning:** the synchronizers are there for a reason. Without them, a signal changing
+    /// close to a clock edge can be sampled as neither 0 nor 1, and be seen with different values
+    /// by different parts of the logic. Only bypass them for inputs that are already synchronous
+    /// to `clk_sys`, or where an occasional wrong reading is acceptable.
     #[inline]
     pub fn set_sync_bypass(&mut self, bypass: bool) {
         let mask = self.id.mask();
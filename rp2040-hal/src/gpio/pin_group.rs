@@ -45,7 +45,7 @@ impl<P: PinId, M: PullType, T: WritePinHList> WritePinHList
     for HCons<Pin<P, FunctionSioOutput, M>, T>
 {
     fn write_mask(&self) -> u32 {
-        self.tail.write_mask()
+        (1 << self.head.id().num) | self.tail.write_mask()
     }
 }
 
@@ -168,3 +168,40 @@ impl Default for PinGroup<HNil> {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use frunk::hlist;
+
+    use super::*;
+    use crate::gpio::{
+        bank0::{Gpio1, Gpio3, Gpio5, Gpio7},
+        func::func_sealed::Function,
+        pull::pull_sealed::PullType,
+        DynFunction, DynPullType, FunctionSio, PullDown, SioInput, SioOutput,
+    };
+
+    /// A type-level pin, built without touching the hardware.
+    fn pin<I: TypeLevelPinId, C: SioConfig>() -> Pin<I, FunctionSio<C>, PullDown> {
+        Pin {
+            id: I::new(),
+            function: Function::from(DynFunction::Sio(C::DYN)),
+            pull_type: PullType::from(DynPullType::Down),
+        }
+    }
+
+    #[test]
+    fn test_write_mask() {
+        let pins = hlist![
+            pin::<Gpio3, SioOutput>(),
+            pin::<Gpio5, SioInput>(),
+            pin::<Gpio7, SioOutput>(),
+        ];
+        assert_eq!(pins.write_mask(), (1 << 3) | (1 << 7));
+        assert_eq!(pins.read_mask(), (1 << 3) | (1 << 5) | (1 << 7));
+
+        let pins = hlist![pin::<Gpio1, SioInput>(), pin::<Gpio3, SioOutput>()];
+        assert_eq!(pins.write_mask(), 1 << 3);
+        assert_eq!(hlist![pin::<Gpio1, SioInput>()].write_mask(), 0);
+    }
+}
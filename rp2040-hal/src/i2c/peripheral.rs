@@ -116,6 +116,47 @@ impl<T: Deref<Target = I2CBlock>, PINS> I2CPeripheralEventIterator<T, PINS> {
         sent
     }
 
+    /// Enables the interrupts matching the events reported by this iterator.
+    ///
+    /// The relevant I2Cx IRQ will fire on start, restart and stop conditions, when the controller
+    /// requests data and when data has been received. In the interrupt handler, call
+    /// [`Iterator::next`] until it returns `None` and service each event as it would be when
+    /// polling: the interrupt sources are only cleared as their events are consumed, so the IRQ
+    /// fires again right away if an event is left pending.
+    ///
+    /// ```ignore
+    /// #[interrupt]
+    /// fn I2C0_IRQ() {
+    ///     critical_section::with(|cs| {
+    ///         let mut i2c = I2C.borrow_ref_mut(cs);
+    ///         let i2c = i2c.as_mut().unwrap();
+    ///         while let Some(event) = i2c.next() {
+    ///             match event {
+    ///                 I2CEvent::TransferRead => { i2c.write(&[0x42]); }
+    ///                 I2CEvent::TransferWrite => { let mut buf = [0; 16]; i2c.read(&mut buf); }
+    ///                 _ => {}
+    ///             }
+    ///         }
+    ///     });
+    /// }
+    /// ```
+    pub fn enable_interrupts(&mut self) {
+        // A set bit unmasks the corresponding interrupt.
+        self.i2c.i2c.ic_intr_mask.write(|w| {
+            w.m_start_det().set_bit();
+            w.m_restart_det().set_bit();
+            w.m_stop_det().set_bit();
+            w.m_rd_req().set_bit();
+            w.m_rx_full().set_bit();
+            w
+        });
+    }
+
+    /// Disables all interrupts of the I2C block.
+    pub fn disable_interrupts(&mut self) {
+        self.i2c.i2c.ic_intr_mask.write(|w| unsafe { w.bits(0) });
+    }
+
     /// Pull up to `usize::min(RX_FIFO_SIZE, buf.len())` bytes from the RX FIFO.
     pub fn read(&mut self, buf: &mut [u8]) -> usize {
         let mut read = 0;
@@ -156,14 +197,12 @@ impl<T: Deref<Target = I2CBlock>, PINS> Iterator for I2CPeripheralEventIterator<
                 Some(I2CEvent::TransferRead)
             }
             State::Read if stat.rd_req().bit_is_set() => Some(I2CEvent::TransferRead),
-            State::Read if stat.restart_det().bit_is_set() => {
-                self.i2c.i2c.ic_clr_restart_det.read();
-                self.state = State::Active;
-                Some(I2CEvent::Restart)
-            }
             State::Write if !self.i2c.rx_fifo_empty() => Some(I2CEvent::TransferWrite),
-            State::Write if stat.restart_det().bit_is_set() => {
+            State::Read | State::Write if stat.restart_det().bit_is_set() => {
+                // A repeated start also raises START_DET, which would otherwise stay set until
+                // the next `Idle` state and be reported as a spurious `Start` after `Stop`.
                 self.i2c.i2c.ic_clr_restart_det.read();
+                self.i2c.i2c.ic_clr_start_det.read();
                 self.state = State::Active;
                 Some(I2CEvent::Restart)
             }
@@ -7,6 +7,8 @@
 //!
 //! # Crate features
 //!
+//! * **async** -
+//!   Async futures for waiting on GPIO inputs and timer ticks
 //! * **chrono** -
 //!   Modifies some RTC access functions to use chrono types instead of a rp2040-hal specific
 //!   DateTime type
@@ -18,6 +20,8 @@
 //!   Disable automatic mapping of language features (like floating point math) to ROM functions
 //! * **eh1_0_alpha** -
 //!   Support alpha release of embedded-hal
+//! * **heapless** -
+//!   Interrupt driven UART buffering through `heapless::spsc` queues, see `uart::QueuedUart`
 //! * **rom-func-cache** -
 //!   Memoize(cache) ROM function pointers on first use to improve performance
 //! * **rt** -
@@ -169,6 +169,29 @@ impl<P: PIOExt> PIO<P> {
             .write(|w| unsafe { w.irq_force().bits(flags) });
     }
 
+    /// Bypass the input synchronizers of the GPIOs whose bit is set in `pins`, or enable them
+    /// again.
+    ///
+    /// Inputs to the PIO state machines pass through two flip-flops synchronizing them to
+    /// `clk_sys`. Bypassing them saves two cycles of latency, but exposes the state machines to
+    /// metastability: only do so for inputs that are already synchronous to `clk_sys`.
+    ///
+    /// Bit n of `pins` corresponds to GPIO n.
+    pub fn set_input_sync_bypass(&mut self, pins: u32, bypass: bool) {
+        self.pio.input_sync_bypass.modify(|r, w| unsafe {
+            if bypass {
+                w.bits(r.bits() | pins)
+            } else {
+                w.bits(r.bits() & !pins)
+            }
+        });
+    }
+
+    /// The GPIOs whose input synchronizers are bypassed, bit n corresponding to GPIO n.
+    pub fn input_sync_bypass(&self) -> u32 {
+        self.pio.input_sync_bypass.read().bits()
+    }
+
     /// Calculates a mask with the `len` right-most bits set.
     fn instruction_mask(len: usize) -> u32 {
         if len < 32 {
@@ -420,8 +443,12 @@ pub trait ValidStateMachine: Sealed {
     /// The index of this state machine (between 0 and 3).
     fn id() -> usize;
     /// The DREQ number for which TX DMA requests are triggered.
+    ///
+    /// This is `8 * pio + sm`: 0 to 3 for the state machines of PIO0, 8 to 11 for PIO1.
     fn tx_dreq() -> u8;
     /// The DREQ number for which RX DMA requests are triggered.
+    ///
+    /// This is `8 * pio + 4 + sm`: 4 to 7 for the state machines of PIO0, 12 to 15 for PIO1.
     fn rx_dreq() -> u8;
 }
 
@@ -1278,6 +1305,9 @@ impl<SM: ValidStateMachine> StateMachine<SM, Running> {
 }
 
 /// PIO RX FIFO handle.
+///
+/// This can be used as the source of a DMA transfer (see [`crate::dma`]) to drain the state
+/// machine into a buffer, paced by its RX DREQ.
 pub struct Rx<SM: ValidStateMachine> {
     block: *const RegisterBlock,
     _phantom: core::marker::PhantomData<SM>,
@@ -1307,8 +1337,8 @@ impl<SM: ValidStateMachine> Rx<SM> {
 
     /// Gets the FIFO's `DREQ` value.
     ///
-    /// This is a value between 0 and 39. Each FIFO on each state machine on
-    /// each PIO has a unique value.
+    /// This is a value between 0 and 15. Each FIFO on each state machine on
+    /// each PIO has a unique value, see [`ValidStateMachine`].
     pub fn dreq_value(&self) -> u8 {
         if self.block as usize == 0x5020_0000usize {
             TREQ_SEL_A::PIO0_RX0 as u8 + (SM::id() as u8)
@@ -1418,6 +1448,9 @@ unsafe impl<SM: ValidStateMachine> ReadTarget for Rx<SM> {
 impl<SM: ValidStateMachine> EndlessReadTarget for Rx<SM> {}
 
 /// PIO TX FIFO handle.
+///
+/// This can be used as the target of a DMA transfer (see [`crate::dma`]) to feed the state
+/// machine from a buffer, paced by its TX DREQ.
 pub struct Tx<SM: ValidStateMachine> {
     block: *const RegisterBlock,
     _phantom: core::marker::PhantomData<SM>,
@@ -1459,8 +1492,8 @@ impl<SM: ValidStateMachine> Tx<SM> {
 
     /// Gets the FIFO's `DREQ` value.
     ///
-    /// This is a value between 0 and 39. Each FIFO on each state machine on
-    /// each PIO has a unique value.
+    /// This is a value between 0 and 15. Each FIFO on each state machine on
+    /// each PIO has a unique value, see [`ValidStateMachine`].
     pub fn dreq_value(&self) -> u8 {
         if self.block as usize == 0x5020_0000usize {
             TREQ_SEL_A::PIO0_TX0 as u8 + (SM::id() as u8)
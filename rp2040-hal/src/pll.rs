@@ -94,8 +94,19 @@ pub enum Error {
 
     /// Bad argument : overflows, bad conversion, ...
     BadArgument,
+
+    /// The PLL did not lock in time.
+    ///
+    /// The wait is bounded by a number of polls, not by a duration: how long it takes depends
+    /// on the frequency `clk_sys` runs at.
+    LockTimeout,
 }
 
+/// How many times [`setup_pll_blocking`] polls the PLL before giving up.
+///
+/// This is a poll count rather than a duration, so the actual timeout scales with `clk_sys`.
+const LOCK_POLLS: u32 = 100_000;
+
 /// Parameters for a PLL.
 pub struct PLLConfig {
     /// Voltage Controlled Oscillator frequency.
@@ -281,6 +292,9 @@ impl<D: PhaseLockedLoopDevice> PhaseLockedLoop<Locked, D> {
 }
 
 /// Blocking helper method to setup the PLL without going through all the steps.
+///
+/// Returns [`Error::LockTimeout`] instead of hanging if the PLL never locks within a fixed
+/// number of polls.
 pub fn setup_pll_blocking<D: PhaseLockedLoopDevice>(
     dev: D,
     xosc_frequency: HertzU32,
@@ -296,7 +310,9 @@ pub fn setup_pll_blocking<D: PhaseLockedLoopDevice>(
     let initialized_pll =
         PhaseLockedLoop::new(dev, xosc_frequency.convert(), config)?.initialize(resets);
 
-    let locked_pll_token = nb::block!(initialized_pll.await_lock()).unwrap();
+    let locked_pll_token = (0..LOCK_POLLS)
+        .find_map(|_| initialized_pll.await_lock().ok())
+        .ok_or(Error::LockTimeout)?;
 
     Ok(initialized_pll.get_locked(locked_pll_token))
 }
@@ -80,6 +80,7 @@ use core::marker::PhantomData;
 
 use embedded_dma::Word;
 use embedded_hal::PwmPin;
+use fugit::{HertzU32, MicrosDurationU32, MicrosDurationU64};
 
 use crate::{
     atomic_register_access::{write_bitmask_clear, write_bitmask_set},
@@ -87,6 +88,7 @@ use crate::{
     gpio::{bank0::*, AnyPin, FunctionPwm, Pin, ValidFunction},
     pac::{self, dma::ch::ch_al1_ctrl::TREQ_SEL_A, PWM},
     resets::SubsystemReset,
+    timer::Timer,
     typelevel::{Is, Sealed},
 };
 
@@ -768,6 +770,64 @@ impl<S: SliceId, M: ValidSliceInputMode<S>> Slice<S, M> {
     {
         pin.into().into_function()
     }
+
+    /// Count the channel B input over a gate time measured with the `timer`.
+    ///
+    /// The counter is reset and the slice is enabled for `gate_time`, then disabled again.
+    /// In [`CountRisingEdge`] and [`CountFallingEdge`] modes, this returns the number of edges
+    /// seen during the gate time. In [`InputHighRunning`] mode, it returns the number of system
+    /// clock cycles during which the input was high.
+    ///
+    /// The counter only advances once every `div_int + div_frac / 16` edges or cycles, the
+    /// returned count is scaled back by the divider. A divider above 1 extends the range before
+    /// the counter wraps, at the cost of resolution.
+    ///
+    /// Returns `None` if the counter wrapped past the top value during the gate time.
+    pub fn count_during(&mut self, timer: &Timer, gate_time: MicrosDurationU32) -> Option<u32> {
+        let gate_time: MicrosDurationU64 = gate_time.into();
+
+        self.disable();
+        self.set_counter(0);
+        self.clear_interrupt();
+
+        let start = timer.get_counter();
+        self.enable();
+        while timer.get_counter() - start < gate_time {}
+        self.disable();
+
+        if self.has_overflown() {
+            None
+        } else {
+            // The divider is a 8.4 fixed point number, an integer part of 0 means 256.
+            let div_int = match self.regs.read_div_int() {
+                0 => 256,
+                div_int => u32::from(div_int),
+            };
+            let div = div_int * 16 + u32::from(self.regs.read_div_frac());
+            Some(u32::from(self.get_counter()) * div / 16)
+        }
+    }
+
+    /// Measure the frequency of the channel B input over a gate time measured with the `timer`.
+    ///
+    /// This is only meaningful in [`CountRisingEdge`] or [`CountFallingEdge`] modes. Longer gate
+    /// times give a finer resolution (1 / gate_time), but the number of edges seen during the
+    /// gate time, divided by the clock divider, must fit the 16 bit counter (assuming the default
+    /// top of 65535). See [`Slice::count_during`] for the effect of the divider.
+    ///
+    /// Returns `None` if the counter wrapped or the gate time is zero.
+    pub fn measure_frequency(
+        &mut self,
+        timer: &Timer,
+        gate_time: MicrosDurationU32,
+    ) -> Option<HertzU32> {
+        if gate_time.to_micros() == 0 {
+            return None;
+        }
+        let count = self.count_during(timer, gate_time)?;
+        let freq = u64::from(count) * 1_000_000 / u64::from(gate_time.to_micros());
+        Some(HertzU32::from_raw(freq as u32))
+    }
 }
 
 /// Type representing DMA access to PWM cc register.
@@ -34,6 +34,25 @@ pub enum CoreId {
     Core1 = 1,
 }
 
+/// Error returned by [`Sio::read_group`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ReadGroupError {
+    /// The group is empty or extends past the last bank0 pin.
+    InvalidRange,
+    /// One of the pins of the group is not configured as an SIO input.
+    NotAnInput,
+}
+
+/// Mask of `width` bank0 pins starting at `base_pin`.
+fn group_mask(base_pin: u8, width: u8) -> Option<u32> {
+    let end = base_pin.checked_add(width)?;
+    if width == 0 || end > 30 {
+        return None;
+    }
+    Some(((1u32 << width) - 1) << base_pin)
+}
+
 /// Marker struct for ownership of SIO gpio bank0
 pub struct SioGpioBank0 {
     _private: (),
@@ -104,6 +123,34 @@ impl Sio {
         unsafe { (*pac::SIO::PTR).gpio_in.read().bits() }
     }
 
+    /// Reads `width` contiguous bank0 pins starting at `base_pin` at once.
+    ///
+    /// The state of `base_pin` is returned in bit 0, the next pin in bit 1 and so on. This is
+    /// meant for sampling parallel buses, eg. an 8 bit bus on Gpio8 to Gpio15 is read with
+    /// `Sio::read_group(8, 8)`.
+    ///
+    /// All the pins of the group must be configured as SIO inputs.
+    pub fn read_group(base_pin: u8, width: u8) -> Result<u32, ReadGroupError> {
+        let mask = group_mask(base_pin, width).ok_or(ReadGroupError::InvalidRange)?;
+
+        // Safety: Only used for reading
+        let io_bank0 = unsafe { &*pac::IO_BANK0::PTR };
+        let sio = unsafe { &*pac::SIO::PTR };
+        let outputs = sio.gpio_oe.read().bits() & mask;
+        let all_sio = (base_pin..base_pin + width).all(|pin| {
+            io_bank0.gpio[usize::from(pin)]
+                .gpio_ctrl
+                .read()
+                .funcsel()
+                .is_sio()
+        });
+        if outputs != 0 || !all_sio {
+            return Err(ReadGroupError::NotAnInput);
+        }
+
+        Ok((sio.gpio_in.read().bits() & mask) >> base_pin)
+    }
+
     /// Returns whether we are running on Core 0 (`0`) or Core 1 (`1`).
     pub fn core() -> CoreId {
         // Safety: it is always safe to read this read-only register
@@ -810,3 +857,19 @@ interpolators!(
     Interp0 : ([Lane0,0],[Lane1,1]),
     Interp1 : ([Lane0,0],[Lane1,1])
 );
+
+#[cfg(test)]
+mod tests {
+    use super::group_mask;
+
+    #[test]
+    fn test_group_mask() {
+        assert_eq!(group_mask(0, 1), Some(0b1));
+        assert_eq!(group_mask(8, 8), Some(0xFF00));
+        assert_eq!(group_mask(0, 30), Some(0x3FFF_FFFF));
+        assert_eq!(group_mask(29, 1), Some(1 << 29));
+        assert_eq!(group_mask(4, 0), None);
+        assert_eq!(group_mask(29, 2), None);
+        assert_eq!(group_mask(255, 1), None);
+    }
+}
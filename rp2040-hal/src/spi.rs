@@ -54,13 +54,24 @@ impl From<&embedded_hal::spi::Mode> for FrameFormat {
 }
 
 /// SPI frame format
+///
+/// The frame format changes how the chip select (SSPFSS) signal is driven, so it only applies to
+/// peripherals whose chip select is wired to the SPI's CSn pin.
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum FrameFormat {
     /// Motorola SPI format. See section 4.4.3.9 of RP2040 datasheet.
     MotorolaSpi(embedded_hal::spi::Mode),
     /// Texas Instruments synchronous serial frame format. See section 4.4.3.8 of RP2040 datasheet.
+    ///
+    /// Chip select is pulsed high for one clock period before each frame instead of being
+    /// held low during the transfer. Clock polarity and phase are fixed.
     TexasInstrumentsSynchronousSerial,
     /// National Semiconductor Microwire frame format. See section 4.4.3.14 of RP2040 datasheet.
+    ///
+    /// Microwire is half-duplex: each frame is an 8-bit control word sent to the peripheral,
+    /// followed by a reply of the configured data size. Only the 8 lowest bits of each written
+    /// word are sent, and the words read back are the replies. Clock polarity and phase are
+    /// fixed.
     NationalSemiconductorMicrowire,
 }
 
@@ -233,7 +244,7 @@ impl<D: SpiDevice, P: ValidSpiPinout<D>, const DS: u8> Spi<Disabled, D, P, DS> {
             w.dss().bits(data_bits - 1).frf().bits(match &frame_format {
                 FrameFormat::MotorolaSpi(_) => 0x00,
                 FrameFormat::TexasInstrumentsSynchronousSerial => 0x01,
-                FrameFormat::NationalSemiconductorMicrowire => 0x10,
+                FrameFormat::NationalSemiconductorMicrowire => 0x02,
             });
 
             /*
@@ -327,6 +338,20 @@ impl<D: SpiDevice, P: ValidSpiPinout<D>, const DS: u8> Spi<Enabled, D, P, DS> {
         self.device.sspsr.read().bsy().bit_is_set()
     }
 
+    /// Enable the loopback mode
+    ///
+    /// The transmit shift register is internally connected to the receive shift register, so
+    /// every word sent is received back. This allows testing the configuration without any
+    /// wiring. The clock and chip select outputs keep being driven as usual.
+    pub fn enable_loopback(&mut self) {
+        self.device.sspcr1.modify(|_, w| w.lbm().set_bit());
+    }
+
+    /// Disable the loopback mode
+    pub fn disable_loopback(&mut self) {
+        self.device.sspcr1.modify(|_, w| w.lbm().clear_bit());
+    }
+
     /// Disable the spi to reset its configuration
     pub fn disable(self) -> Spi<Disabled, D, P, DS> {
         self.device.sspcr1.modify(|_, w| w.sse().clear_bit());
@@ -248,8 +248,17 @@ impl embedded_hal::timer::Cancel for CountDown<'_> {
     }
 }
 
+mod alarm_sealed {
+    use crate::typelevel::Sealed;
+
+    pub trait AlarmId: Sealed {
+        /// Bit of this alarm in the ARMED and interrupt registers.
+        const MASK: u32;
+    }
+}
+
 /// Alarm abstraction.
-pub trait Alarm: Sealed {
+pub trait Alarm: alarm_sealed::AlarmId {
     /// Clear the interrupt flag.
     ///
     /// The interrupt is unable to trigger a 2nd time until this interrupt is cleared.
@@ -435,6 +444,10 @@ macro_rules! impl_alarm {
 
         impl Sealed for $name {}
 
+        impl alarm_sealed::AlarmId for $name {
+            const MASK: u32 = $armed_bit_mask;
+        }
+
         impl Drop for $name {
             fn drop(&mut self) {
                 self.disable_interrupt();
@@ -479,6 +492,333 @@ impl_alarm!(Alarm3 {
     armed_bit_mask: 0b1000
 });
 
+/// Returned by [`Ticker`] when one or more ticks were missed.
+///
+/// The contained value is the number of skipped ticks.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MissedTicks(pub u64);
+
+/// Fixed-rate ticker.
+///
+/// Ticks are scheduled against absolute timestamps: each deadline is exactly one period after the
+/// previous one, regardless of how late the ticker was waited on. This keeps the phase of the
+/// ticks stable, unlike repeatedly delaying for the period.
+///
+/// If the ticker is waited on so late that whole periods have elapsed, the missed ticks are
+/// skipped and reported as [`MissedTicks`]. The following deadlines stay in phase.
+///
+/// ## Usage
+/// ```no_run
+/// use fugit::ExtU32;
+/// use rp2040_hal::timer::Ticker;
+/// let mut pac = rp2040_hal::pac::Peripherals::take().unwrap();
+/// // Make sure to initialize clocks, otherwise the timer wouldn't work
+/// // properly. Omitted here for terseness.
+/// let clocks: rp2040_hal::clocks::ClocksManager = todo!();
+/// let mut timer = rp2040_hal::Timer::new(pac.TIMER, &mut pac.RESETS, &clocks);
+/// let alarm = timer.alarm_0().unwrap();
+/// // Tick at 1kHz
+/// let mut ticker = Ticker::new(timer, alarm, 1.millis());
+/// loop {
+///     if ticker.wait().is_err() {
+///         // The control loop could not keep up
+///     }
+///     // Run the control loop
+/// }
+/// ```
+pub struct Ticker<A: Alarm> {
+    timer: Timer,
+    alarm: A,
+    period: u64,
+    next: Instant,
+}
+
+impl<A: Alarm> Ticker<A> {
+    /// Creates a new ticker with the given period, starting now.
+    ///
+    /// The first tick happens one period from now.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn new(timer: Timer, alarm: A, period: MicrosDurationU32) -> Self {
+        assert!(period.ticks() > 0, "Ticker period must not be zero");
+        let period = u64::from(period.ticks());
+        let next = timer.get_counter() + MicrosDurationU64::micros(period);
+        Self {
+            timer,
+            alarm,
+            period,
+            next,
+        }
+    }
+
+    /// The period of the ticker.
+    pub fn period(&self) -> MicrosDurationU32 {
+        MicrosDurationU32::micros(self.period as u32)
+    }
+
+    /// The timestamp of the next tick.
+    pub fn next_deadline(&self) -> Instant {
+        self.next
+    }
+
+    /// Restarts the ticker, the next tick happening one period from now.
+    pub fn reset(&mut self) {
+        self.next = self.timer.get_counter() + MicrosDurationU64::micros(self.period);
+    }
+
+    /// Blocks until the next tick.
+    ///
+    /// Returns immediately if the deadline has already passed. See [`MissedTicks`] for what
+    /// happens when more than a period has elapsed since.
+    pub fn wait(&mut self) -> Result<(), MissedTicks> {
+        loop {
+            let now = self.timer.get_counter();
+            if now >= self.next {
+                return self.advance(now);
+            }
+        }
+    }
+
+    /// Releases the underlying timer and alarm.
+    pub fn free(self) -> (Timer, A) {
+        (self.timer, self.alarm)
+    }
+
+    fn advance(&mut self, now: Instant) -> Result<(), MissedTicks> {
+        let (next, missed) = next_tick(self.next.ticks(), self.period, now.ticks());
+        self.next = Instant::from_ticks(next);
+        if missed == 0 {
+            Ok(())
+        } else {
+            Err(MissedTicks(missed))
+        }
+    }
+}
+
+/// Computes the deadline following `now`, given the deadline `next` that has been reached.
+///
+/// Returns the new deadline and the number of ticks that were skipped.
+fn next_tick(next: u64, period: u64, now: u64) -> (u64, u64) {
+    let missed = (now - next) / period;
+    (next + (missed + 1) * period, missed)
+}
+
+/// Measures the width of the pulses on an input, from timestamps taken at its edges.
+///
+/// The edges are timestamped in software: [`PulseMeter::on_rising_edge`] and
+/// [`PulseMeter::on_falling_edge`] are meant to be called from the GPIO interrupt handler, after
+/// checking which edge interrupt fired.
+///
+/// The resolution is the one of the timer, 1µs. Each timestamp is delayed by the interrupt
+/// latency, which is a few µs at the default clocks but grows if other interrupts with the same or
+/// higher priority are running. The measured durations are only as accurate as the variation of
+/// that latency, which is usually fine for ultrasonic range finders or RC receivers. For tighter
+/// measurements, the edges have to be timestamped in hardware: a PWM slice in
+/// [`InputHighRunning`](crate::pwm::InputHighRunning) mode counts the cycles an input is high,
+/// and PIO can sample pins at the system clock rate.
+///
+/// ## Usage
+/// ```ignore
+/// use rp2040_hal::{gpio::Interrupt::{EdgeHigh, EdgeLow}, pac::interrupt, timer::PulseMeter};
+///
+/// #[interrupt]
+/// fn IO_IRQ_BANK0() {
+///     critical_section::with(|cs| {
+///         let (pin, meter) = &mut *STATE.borrow_ref_mut(cs);
+///         if pin.interrupt_status(EdgeHigh) {
+///             pin.clear_interrupt(EdgeHigh);
+///             meter.on_rising_edge();
+///         }
+///         if pin.interrupt_status(EdgeLow) {
+///             pin.clear_interrupt(EdgeLow);
+///             meter.on_falling_edge();
+///         }
+///     });
+/// }
+///
+/// // Later, eg. in the main loop
+/// let echo_width = critical_section::with(|cs| STATE.borrow_ref(cs).1.last_high());
+/// ```
+pub struct PulseMeter {
+    timer: Timer,
+    last_edge: Option<(bool, Instant)>,
+    high: Option<MicrosDurationU64>,
+    low: Option<MicrosDurationU64>,
+}
+
+impl PulseMeter {
+    /// Creates a new pulse meter, with no pulse measured yet.
+    pub fn new(timer: Timer) -> Self {
+        Self {
+            timer,
+            last_edge: None,
+            high: None,
+            low: None,
+        }
+    }
+
+    /// Records a rising edge, timestamped now.
+    pub fn on_rising_edge(&mut self) {
+        let now = self.timer.get_counter();
+        self.on_edge_at(true, now);
+    }
+
+    /// Records a falling edge, timestamped now.
+    pub fn on_falling_edge(&mut self) {
+        let now = self.timer.get_counter();
+        self.on_edge_at(false, now);
+    }
+
+    /// Records an edge with an already taken timestamp.
+    ///
+    /// If two edges in the same direction are recorded in a row (ie. an edge was missed), the
+    /// interval between them is not measured.
+    pub fn on_edge_at(&mut self, rising: bool, at: Instant) {
+        if let Some((last_rising, last_at)) = self.last_edge {
+            if let (true, Some(width)) = (last_rising != rising, at.checked_duration_since(last_at))
+            {
+                if rising {
+                    self.low = Some(width);
+                } else {
+                    self.high = Some(width);
+                }
+            }
+        }
+        self.last_edge = Some((rising, at));
+    }
+
+    /// The duration of the last complete high pulse.
+    pub fn last_high(&self) -> Option<MicrosDurationU64> {
+        self.high
+    }
+
+    /// The duration of the last complete low pulse.
+    pub fn last_low(&self) -> Option<MicrosDurationU64> {
+        self.low
+    }
+
+    /// The timestamp of the last recorded edge, and whether it was rising.
+    pub fn last_edge(&self) -> Option<(bool, Instant)> {
+        self.last_edge
+    }
+
+    /// Forgets all recorded edges and measured pulses.
+    pub fn reset(&mut self) {
+        self.last_edge = None;
+        self.high = None;
+        self.low = None;
+    }
+}
+
+#[cfg(feature = "async")]
+mod asynch {
+    use core::{
+        cell::RefCell,
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll, Waker},
+    };
+
+    use critical_section::Mutex;
+
+    use super::{alarm_sealed::AlarmId, Alarm, MissedTicks, Ticker};
+    use crate::{atomic_register_access::write_bitmask_clear, pac};
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NO_WAKER: Mutex<RefCell<Option<Waker>>> = Mutex::new(RefCell::new(None));
+    static WAKERS: [Mutex<RefCell<Option<Waker>>>; 4] = [NO_WAKER; 4];
+
+    fn slot<A: AlarmId>() -> usize {
+        A::MASK.trailing_zeros() as usize
+    }
+
+    /// Wakes the [`Ticker`] futures whose alarm fired.
+    ///
+    /// This must be called from the TIMER_IRQ_n interrupt handler of the alarms used by tickers.
+    /// Alarms that are not awaited on are left untouched, so this can be combined with other
+    /// interrupt handling code.
+    pub fn on_interrupt() {
+        // Safety: Only bits belonging to alarms with a pending future are touched
+        let timer = unsafe { &*pac::TIMER::PTR };
+        let ints = timer.ints.read().bits();
+
+        critical_section::with(|cs| {
+            for (index, slot) in WAKERS.iter().enumerate() {
+                let mask = 1 << index;
+                if ints & mask == 0 {
+                    continue;
+                }
+                if let Some(waker) = slot.borrow_ref_mut(cs).take() {
+                    unsafe {
+                        write_bitmask_clear(timer.inte.as_ptr(), mask);
+                        write_bitmask_clear(timer.intf.as_ptr(), mask);
+                        timer.intr.write_with_zero(|w| w.bits(mask));
+                    }
+                    waker.wake();
+                }
+            }
+        });
+    }
+
+    /// Future returned by [`Ticker::next`].
+    ///
+    /// Dropping it cancels the alarm.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct TickFuture<'t, A: Alarm> {
+        ticker: &'t mut Ticker<A>,
+    }
+
+    impl<'t, A: Alarm> Future for TickFuture<'t, A> {
+        type Output = Result<(), MissedTicks>;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let ticker = &mut *self.ticker;
+            let now = ticker.timer.get_counter();
+            if now >= ticker.next {
+                return Poll::Ready(ticker.advance(now));
+            }
+
+            critical_section::with(|cs| {
+                WAKERS[slot::<A>()]
+                    .borrow_ref_mut(cs)
+                    .replace(cx.waker().clone());
+                // Cannot fail: the deadline is at most one period away.
+                let _ = ticker.alarm.schedule_at(ticker.next);
+                ticker.alarm.enable_interrupt();
+            });
+            Poll::Pending
+        }
+    }
+
+    impl<'t, A: Alarm> Drop for TickFuture<'t, A> {
+        fn drop(&mut self) {
+            let alarm = &mut self.ticker.alarm;
+            critical_section::with(|cs| {
+                alarm.disable_interrupt();
+                let _ = alarm.cancel();
+                alarm.clear_interrupt();
+                WAKERS[slot::<A>()].borrow_ref_mut(cs).take();
+            });
+        }
+    }
+
+    impl<A: Alarm> Ticker<A> {
+        /// Waits for the next tick.
+        ///
+        /// This enables the alarm interrupt while the future is pending. Its TIMER_IRQ_n
+        /// interrupt must be unmasked in the NVIC and its handler must call [`on_interrupt`].
+        #[allow(clippy::should_implement_trait)]
+        pub fn next(&mut self) -> TickFuture<'_, A> {
+            TickFuture { ticker: self }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+pub use asynch::{on_interrupt, TickFuture};
+
 /// Support for RTIC monotonic trait.
 #[cfg(feature = "rtic-monotonic")]
 pub mod monotonic {
@@ -525,3 +865,46 @@ pub mod monotonic {
         unsafe fn reset(&mut self) {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{next_tick, Instant, PulseMeter, Timer};
+    use fugit::MicrosDurationU64;
+
+    #[test]
+    fn test_next_tick() {
+        // On time or late by less than a period
+        assert_eq!(next_tick(1000, 100, 1000), (1100, 0));
+        assert_eq!(next_tick(1000, 100, 1099), (1100, 0));
+        // Whole periods missed, phase is kept
+        assert_eq!(next_tick(1000, 100, 1100), (1200, 1));
+        assert_eq!(next_tick(1000, 100, 1350), (1400, 3));
+    }
+
+    #[test]
+    fn test_pulse_meter() {
+        let mut meter = PulseMeter::new(Timer { _private: () });
+        let at = Instant::from_ticks;
+        let us = MicrosDurationU64::micros;
+
+        meter.on_edge_at(true, at(100));
+        assert_eq!((meter.last_high(), meter.last_low()), (None, None));
+        meter.on_edge_at(false, at(250));
+        assert_eq!((meter.last_high(), meter.last_low()), (Some(us(150)), None));
+        meter.on_edge_at(true, at(1250));
+        assert_eq!(meter.last_low(), Some(us(1000)));
+
+        // A missed falling edge: the rising to rising interval isn't a pulse
+        meter.on_edge_at(true, at(2000));
+        assert_eq!(
+            (meter.last_high(), meter.last_low()),
+            (Some(us(150)), Some(us(1000)))
+        );
+        meter.on_edge_at(false, at(2010));
+        assert_eq!(meter.last_high(), Some(us(10)));
+
+        meter.reset();
+        meter.on_edge_at(false, at(3000));
+        assert_eq!((meter.last_high(), meter.last_low()), (None, None));
+    }
+}
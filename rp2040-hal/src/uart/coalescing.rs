@@ -0,0 +1,169 @@
+//! Universal Asynchronous Receiver Transmitter - Coalescing Writer
+//!
+//! This module provides a buffer in front of a UART writer, so many small writes (eg. from
+//! `write!`) are gathered and handed to the writer in a single burst. This only batches the
+//! calls: the bytes still go through the writer one at a time, waiting for room in the TX FIFO
+//! like any other write.
+
+use core::convert::Infallible;
+use core::fmt;
+use embedded_hal::serial::Write;
+
+/// Buffers up to `N` bytes before pushing them to the wrapped writer.
+///
+/// The buffer is flushed when it is full or when [`flush`](Self::flush) is called. By default,
+/// any buffered data is also flushed when the `CoalescingWriter` is dropped.
+///
+/// ```no_run
+/// # use rp2040_hal::{gpio::Pins, pac, sio::Sio, uart::{CoalescingWriter, UartConfig, UartPeripheral}};
+/// # use fugit::RateExtU32;
+/// # let mut peripherals = pac::Peripherals::take().unwrap();
+/// # let sio = Sio::new(peripherals.SIO);
+/// # let pins = Pins::new(peripherals.IO_BANK0, peripherals.PADS_BANK0, sio.gpio_bank0, &mut peripherals.RESETS);
+/// # let pins = (pins.gpio0.into_function(), pins.gpio1.into_function());
+/// # let mut uart = UartPeripheral::new(peripherals.UART0, pins, &mut peripherals.RESETS)
+/// #     .enable(UartConfig::default(), 125.MHz()).unwrap();
+/// use core::fmt::Write;
+///
+/// let mut writer = CoalescingWriter::<_, 32>::new(&mut uart);
+/// for value in 0..10 {
+///     write!(writer, "{} ", value).unwrap();
+/// }
+/// writer.flush();
+/// ```
+pub struct CoalescingWriter<'w, W, const N: usize>
+where
+    W: Write<u8, Error = Infallible>,
+{
+    writer: &'w mut W,
+    buffer: [u8; N],
+    len: usize,
+    flush_on_drop: bool,
+}
+
+impl<'w, W, const N: usize> CoalescingWriter<'w, W, N>
+where
+    W: Write<u8, Error = Infallible>,
+{
+    /// Creates a new, empty `CoalescingWriter` in front of `writer`.
+    pub fn new(writer: &'w mut W) -> Self {
+        Self {
+            writer,
+            buffer: [0; N],
+            len: 0,
+            flush_on_drop: true,
+        }
+    }
+
+    /// Selects whether buffered data is flushed when the `CoalescingWriter` is dropped.
+    ///
+    /// Default is true
+    pub fn set_flush_on_drop(&mut self, enable: bool) {
+        self.flush_on_drop = enable;
+    }
+
+    /// Number of bytes currently buffered.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Is the buffer empty?
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Buffers bytes, flushing to the wrapped writer every time the buffer is full.
+    pub fn write(&mut self, mut data: &[u8]) {
+        if N == 0 {
+            self.write_through(data);
+            return;
+        }
+
+        while !data.is_empty() {
+            let count = usize::min(N - self.len, data.len());
+            self.buffer[self.len..self.len + count].copy_from_slice(&data[..count]);
+            self.len += count;
+            data = &data[count..];
+
+            if self.len == N {
+                self.flush();
+            }
+        }
+    }
+
+    /// Pushes all buffered bytes to the wrapped writer.
+    ///
+    /// The bytes are written one at a time, this function blocks until all of them have been
+    /// accepted by the writer.
+    pub fn flush(&mut self) {
+        for &byte in &self.buffer[..self.len] {
+            let _ = nb::block!(self.writer.write(byte));
+        }
+        self.len = 0;
+    }
+
+    /// Discards all buffered bytes without writing them.
+    pub fn reset(&mut self) {
+        self.len = 0;
+    }
+
+    fn write_through(&mut self, data: &[u8]) {
+        for &byte in data {
+            let _ = nb::block!(self.writer.write(byte));
+        }
+    }
+}
+
+impl<'w, W, const N: usize> fmt::Write for CoalescingWriter<'w, W, N>
+where
+    W: Write<u8, Error = Infallible>,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write(s.as_bytes());
+        Ok(())
+    }
+}
+
+impl<'w, W, const N: usize> Drop for CoalescingWriter<'w, W, N>
+where
+    W: Write<u8, Error = Infallible>,
+{
+    fn drop(&mut self) {
+        if self.flush_on_drop {
+            self.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uart::test_utils::Sink;
+
+    #[test]
+    fn test_coalescing_writer() {
+        let mut sink = Sink::default();
+
+        let mut writer = CoalescingWriter::<_, 4>::new(&mut sink);
+        writer.write(b"ab");
+        assert_eq!(writer.len(), 2);
+        writer.write(b"cdefg");
+        assert_eq!(writer.len(), 3);
+        drop(writer);
+        assert_eq!(sink.written(), b"abcdefg");
+
+        let mut writer = CoalescingWriter::<_, 4>::new(&mut sink);
+        writer.write(b"xy");
+        writer.reset();
+        assert!(writer.is_empty());
+        writer.write(b"z");
+        writer.set_flush_on_drop(false);
+        drop(writer);
+        assert_eq!(sink.written(), b"abcdefg");
+
+        let mut writer = CoalescingWriter::<_, 0>::new(&mut sink);
+        writer.write(b"hi");
+        drop(writer);
+        assert_eq!(sink.written(), b"abcdefghi");
+    }
+}
@@ -0,0 +1,199 @@
+//! Universal Asynchronous Receiver Transmitter - Echo
+//!
+//! This module echoes the received bytes back to the sender, as expected by interactive serial
+//! consoles.
+
+use core::convert::Infallible;
+use embedded_hal::serial::{Read, Write};
+
+/// How received line endings are echoed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EchoNewline {
+    /// Line endings are echoed as received.
+    #[default]
+    Verbatim,
+    /// A received CR or LF is echoed as CR LF, so the terminal moves to the start of the next
+    /// line. The LF of a received CR LF pair is not echoed again.
+    CrLf,
+}
+
+/// Wraps the two halves of a split UART and echoes every byte read back out.
+///
+/// The received bytes are returned unchanged, only the echo is translated. Reading never waits
+/// for the echo to be sent, as long as there is room in the TX FIFO.
+///
+/// ```no_run
+/// # use rp2040_hal::{gpio::Pins, pac, sio::Sio, uart::{EchoNewline, EchoUart, UartConfig, UartPeripheral}};
+/// # use fugit::RateExtU32;
+/// # let mut peripherals = pac::Peripherals::take().unwrap();
+/// # let sio = Sio::new(peripherals.SIO);
+/// # let pins = Pins::new(peripherals.IO_BANK0, peripherals.PADS_BANK0, sio.gpio_bank0, &mut peripherals.RESETS);
+/// # let pins = (pins.gpio0.into_function(), pins.gpio1.into_function());
+/// # let uart = UartPeripheral::new(peripherals.UART0, pins, &mut peripherals.RESETS)
+/// #     .enable(UartConfig::default(), 125.MHz()).unwrap();
+/// let (reader, writer) = uart.split();
+/// let mut console = EchoUart::new(reader, writer);
+/// console.set_newline(EchoNewline::CrLf);
+/// console.set_backspace(true);
+/// loop {
+///     if let Ok(byte) = nb::block!(console.read()) {
+///         /* handle the byte */
+///     }
+/// }
+/// ```
+pub struct EchoUart<R, W>
+where
+    R: Read<u8>,
+    W: Write<u8, Error = Infallible>,
+{
+    reader: R,
+    writer: W,
+    echo: bool,
+    newline: EchoNewline,
+    backspace: bool,
+    last_cr: bool,
+}
+
+impl<R, W> EchoUart<R, W>
+where
+    R: Read<u8>,
+    W: Write<u8, Error = Infallible>,
+{
+    /// Creates a new `EchoUart`, echoing line endings and backspaces verbatim.
+    pub fn new(reader: R, writer: W) -> Self {
+        Self {
+            reader,
+            writer,
+            echo: true,
+            newline: EchoNewline::Verbatim,
+            backspace: false,
+            last_cr: false,
+        }
+    }
+
+    /// Enables or disables the echo, eg. while a password is typed in.
+    ///
+    /// Default is true
+    pub fn set_echo(&mut self, enable: bool) {
+        self.echo = enable;
+    }
+
+    /// Selects how received line endings are echoed.
+    pub fn set_newline(&mut self, newline: EchoNewline) {
+        self.newline = newline;
+    }
+
+    /// Selects whether a received backspace (BS or DEL) is echoed as BS, space, BS, which erases
+    /// the last character on the terminal.
+    ///
+    /// Default is false
+    pub fn set_backspace(&mut self, enable: bool) {
+        self.backspace = enable;
+    }
+
+    /// Reads a byte and echoes it.
+    pub fn read(&mut self) -> nb::Result<u8, R::Error> {
+        let byte = self.reader.read()?;
+        if self.echo {
+            let (echo, len) = self.echo_bytes(byte);
+            for &byte in &echo[..len] {
+                let _ = nb::block!(self.writer.write(byte));
+            }
+        }
+        self.last_cr = byte == b'\r';
+        Ok(byte)
+    }
+
+    /// Writes a byte, without echo processing.
+    pub fn write(&mut self, byte: u8) -> nb::Result<(), Infallible> {
+        self.writer.write(byte)
+    }
+
+    /// Gives access to the writer, eg. to print a prompt.
+    pub fn writer(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Releases the reader and the writer.
+    pub fn free(self) -> (R, W) {
+        (self.reader, self.writer)
+    }
+
+    fn echo_bytes(&self, byte: u8) -> ([u8; 3], usize) {
+        match byte {
+            b'\r' | b'\n' if self.newline == EchoNewline::CrLf => {
+                if byte == b'\n' && self.last_cr {
+                    ([0; 3], 0)
+                } else {
+                    ([b'\r', b'\n', 0], 2)
+                }
+            }
+            0x08 | 0x7F if self.backspace => ([0x08, b' ', 0x08], 3),
+            _ => ([byte, 0, 0], 1),
+        }
+    }
+}
+
+impl<R, W> Read<u8> for EchoUart<R, W>
+where
+    R: Read<u8>,
+    W: Write<u8, Error = Infallible>,
+{
+    type Error = R::Error;
+
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        EchoUart::read(self)
+    }
+}
+
+impl<R, W> Write<u8> for EchoUart<R, W>
+where
+    R: Read<u8>,
+    W: Write<u8, Error = Infallible>,
+{
+    type Error = Infallible;
+
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        self.writer.write(word)
+    }
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uart::test_utils::{Sink, Source};
+
+    fn echo(input: &[u8], setup: impl FnOnce(&mut EchoUart<Source<Infallible>, Sink>)) -> Sink {
+        let mut received = [Ok(0); 16];
+        for (item, &byte) in received.iter_mut().zip(input) {
+            *item = Ok(byte);
+        }
+        let mut uart = EchoUart::new(Source(&received[..input.len()]), Sink::default());
+        setup(&mut uart);
+        for &byte in input {
+            assert_eq!(uart.read(), Ok(byte));
+        }
+        assert_eq!(uart.read(), Err(nb::Error::WouldBlock));
+        uart.free().1
+    }
+
+    #[test]
+    fn test_echo_uart() {
+        let sink = echo(b"ab\r\x7f", |_| {});
+        assert_eq!(sink.written(), b"ab\r\x7f");
+
+        let sink = echo(b"a\rb\nc\r\nd\x08", |uart| {
+            uart.set_newline(EchoNewline::CrLf);
+            uart.set_backspace(true);
+        });
+        assert_eq!(sink.written(), b"a\r\nb\r\nc\r\nd\x08 \x08");
+
+        let sink = echo(b"secret", |uart| uart.set_echo(false));
+        assert!(sink.written().is_empty());
+    }
+}
@@ -0,0 +1,386 @@
+//! Universal Asynchronous Receiver Transmitter - Frame Reader
+//!
+//! This module assembles length-prefixed, checksummed frames out of the received bytes.
+
+use embedded_hal::serial::Read;
+use fugit::MicrosDurationU32;
+
+use super::ReadErrorType;
+use crate::timer::Timer;
+
+/// Errors reported by a [`FrameReader`].
+///
+/// After any of them, the partially received frame is discarded and the reader starts over
+/// with the next frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FrameError {
+    /// The received checksum doesn't match the payload.
+    BadChecksum,
+    /// The announced payload length doesn't fit in the buffer. The payload and checksum are
+    /// skipped.
+    TooLong,
+    /// No complete frame was received in time.
+    Timeout,
+    /// The UART reported an error.
+    Read(ReadErrorType),
+}
+
+enum State {
+    Length { read: usize, value: u32 },
+    Payload { len: usize, read: usize },
+    Checksum { len: usize, read: usize },
+    Discard { remaining: u64 },
+}
+
+/// Assembles frames made of a length field, a payload and a checksum.
+///
+/// - The length field is `LEN` bytes wide (1 to 4, other values fail to build), big-endian, and
+///   holds the payload length.
+/// - The checksum is `CRC` bytes wide and covers the payload only. It is computed by the
+///   `checksum` function given to [`FrameReader::new`] and compared with the received bytes.
+///
+/// Payloads are stored in a user provided buffer, which also bounds the accepted length.
+///
+/// ```no_run
+/// # use rp2040_hal::{gpio::Pins, pac, sio::Sio, uart::{FrameReader, UartConfig, UartPeripheral}};
+/// # use fugit::RateExtU32;
+/// # let mut peripherals = pac::Peripherals::take().unwrap();
+/// # let sio = Sio::new(peripherals.SIO);
+/// # let pins = Pins::new(peripherals.IO_BANK0, peripherals.PADS_BANK0, sio.gpio_bank0, &mut peripherals.RESETS);
+/// # let pins = (pins.gpio0.into_function(), pins.gpio1.into_function());
+/// # let mut uart = UartPeripheral::new(peripherals.UART0, pins, &mut peripherals.RESETS)
+/// #     .enable(UartConfig::default(), 125.MHz()).unwrap();
+/// // 2 bytes length, 1 byte checksum: the sum of the payload bytes
+/// let checksum = |payload: &[u8]| [payload.iter().fold(0u8, |sum, b| sum.wrapping_add(*b))];
+/// let mut buffer = [0; 64];
+/// let mut frames = FrameReader::<_, 2, 1>::new(&mut buffer, checksum);
+/// loop {
+///     match frames.read(&mut uart) {
+///         Ok(frame) => { /* handle the frame */ }
+///         Err(nb::Error::Other(_)) => { /* a frame was dropped */ }
+///         Err(nb::Error::WouldBlock) => { /* do something else */ }
+///     }
+/// }
+/// ```
+pub struct FrameReader<'b, F, const LEN: usize, const CRC: usize>
+where
+    F: FnMut(&[u8]) -> [u8; CRC],
+{
+    buffer: &'b mut [u8],
+    checksum: F,
+    received_checksum: [u8; CRC],
+    state: State,
+}
+
+impl<'b, F, const LEN: usize, const CRC: usize> FrameReader<'b, F, LEN, CRC>
+where
+    F: FnMut(&[u8]) -> [u8; CRC],
+{
+    /// Fails the build when `LEN` is out of range, as the length is accumulated in a `u32`.
+    const VALID_LEN: () = assert!(LEN >= 1 && LEN <= 4, "LEN must be between 1 and 4");
+
+    /// Creates a new frame reader, storing payloads into `buffer`.
+    pub fn new(buffer: &'b mut [u8], checksum: F) -> Self {
+        let () = Self::VALID_LEN;
+        Self {
+            buffer,
+            checksum,
+            received_checksum: [0; CRC],
+            state: State::Length { read: 0, value: 0 },
+        }
+    }
+
+    /// Discards any partially received frame.
+    pub fn reset(&mut self) {
+        self.state = State::Length { read: 0, value: 0 };
+    }
+
+    /// Feeds a received byte.
+    ///
+    /// Returns the payload once a complete and valid frame has been received.
+    pub fn push(&mut self, byte: u8) -> Result<Option<&[u8]>, FrameError> {
+        Ok(self.push_byte(byte)?.map(|len| &self.buffer[..len]))
+    }
+
+    /// Reads all the available bytes, until a complete frame has been received.
+    ///
+    /// Returns `WouldBlock` once the UART has no more data and the frame is still incomplete.
+    pub fn read<R>(&mut self, uart: &mut R) -> nb::Result<&[u8], FrameError>
+    where
+        R: Read<u8, Error = ReadErrorType>,
+    {
+        let len = loop {
+            match uart.read() {
+                Ok(byte) => {
+                    if let Some(len) = self.push_byte(byte)? {
+                        break len;
+                    }
+                }
+                Err(nb::Error::WouldBlock) => return Err(nb::Error::WouldBlock),
+                Err(nb::Error::Other(e)) => {
+                    self.reset();
+                    return Err(nb::Error::Other(FrameError::Read(e)));
+                }
+            }
+        };
+
+        Ok(&self.buffer[..len])
+    }
+
+    /// Blocks until a complete frame has been received, or `timeout` has elapsed.
+    ///
+    /// On timeout, the partially received frame is discarded.
+    pub fn read_blocking<R>(
+        &mut self,
+        uart: &mut R,
+        timer: &Timer,
+        timeout: MicrosDurationU32,
+    ) -> Result<&[u8], FrameError>
+    where
+        R: Read<u8, Error = ReadErrorType>,
+    {
+        let deadline = timer.get_counter() + timeout;
+        self.read_blocking_with(uart, || timer.get_counter() >= deadline)
+    }
+
+    /// Blocks until a complete frame has been received, or `timed_out` returns true.
+    fn read_blocking_with<R>(
+        &mut self,
+        uart: &mut R,
+        mut timed_out: impl FnMut() -> bool,
+    ) -> Result<&[u8], FrameError>
+    where
+        R: Read<u8, Error = ReadErrorType>,
+    {
+        let len = loop {
+            match uart.read() {
+                Ok(byte) => {
+                    if let Some(len) = self.push_byte(byte)? {
+                        break len;
+                    }
+                }
+                Err(nb::Error::WouldBlock) => {
+                    if timed_out() {
+                        self.reset();
+                        return Err(FrameError::Timeout);
+                    }
+                }
+                Err(nb::Error::Other(e)) => {
+                    self.reset();
+                    return Err(FrameError::Read(e));
+                }
+            }
+        };
+
+        Ok(&self.buffer[..len])
+    }
+
+    fn push_byte(&mut self, byte: u8) -> Result<Option<usize>, FrameError> {
+        match self.state {
+            State::Length { read, value } => {
+                let value = (value << 8) | u32::from(byte);
+                if read + 1 < LEN {
+                    self.state = State::Length {
+                        read: read + 1,
+                        value,
+                    };
+                    return Ok(None);
+                }
+
+                let len = value as usize;
+                if len > self.buffer.len() {
+                    self.state = State::Discard {
+                        remaining: u64::from(value) + CRC as u64,
+                    };
+                    return Err(FrameError::TooLong);
+                }
+                self.state = State::Payload { len, read: 0 };
+                if len == 0 {
+                    return self.payload_done(0);
+                }
+            }
+            State::Payload { len, read } => {
+                self.buffer[read] = byte;
+                if read + 1 < len {
+                    self.state = State::Payload {
+                        len,
+                        read: read + 1,
+                    };
+                } else {
+                    return self.payload_done(len);
+                }
+            }
+            State::Checksum { len, read } => {
+                self.received_checksum[read] = byte;
+                if read + 1 < CRC {
+                    self.state = State::Checksum {
+                        len,
+                        read: read + 1,
+                    };
+                } else {
+                    return self.frame_done(len);
+                }
+            }
+            State::Discard { remaining } => {
+                if remaining > 1 {
+                    self.state = State::Discard {
+                        remaining: remaining - 1,
+                    };
+                } else {
+                    self.reset();
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn payload_done(&mut self, len: usize) -> Result<Option<usize>, FrameError> {
+        if CRC == 0 {
+            return self.frame_done(len);
+        }
+        self.state = State::Checksum { len, read: 0 };
+        Ok(None)
+    }
+
+    fn frame_done(&mut self, len: usize) -> Result<Option<usize>, FrameError> {
+        self.reset();
+        if (self.checksum)(&self.buffer[..len]) == self.received_checksum {
+            Ok(Some(len))
+        } else {
+            Err(FrameError::BadChecksum)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uart::test_utils::Source;
+
+    fn sum(payload: &[u8]) -> [u8; 1] {
+        [payload.iter().fold(0u8, |sum, b| sum.wrapping_add(*b))]
+    }
+
+    #[test]
+    fn test_frame_reader() {
+        let mut buffer = [0; 4];
+        let mut frames = FrameReader::<_, 2, 1>::new(&mut buffer, sum);
+
+        // valid frame
+        for &byte in &[0, 3, 1, 2, 3] {
+            assert_eq!(frames.push(byte), Ok(None));
+        }
+        assert_eq!(frames.push(6), Ok(Some(&[1, 2, 3][..])));
+
+        // bad checksum, then resync on the next frame
+        for &byte in &[0, 2, 1, 2] {
+            assert_eq!(frames.push(byte), Ok(None));
+        }
+        assert_eq!(frames.push(0), Err(FrameError::BadChecksum));
+        for &byte in &[0, 1, 7] {
+            assert_eq!(frames.push(byte), Ok(None));
+        }
+        assert_eq!(frames.push(7), Ok(Some(&[7][..])));
+
+        // too long: the payload and checksum are skipped
+        assert_eq!(frames.push(0), Ok(None));
+        assert_eq!(frames.push(5), Err(FrameError::TooLong));
+        for &byte in &[1, 2, 3, 4, 5, 15] {
+            assert_eq!(frames.push(byte), Ok(None));
+        }
+
+        // empty frame
+        assert_eq!(frames.push(0), Ok(None));
+        assert_eq!(frames.push(0), Ok(None));
+        assert_eq!(frames.push(0), Ok(Some(&[][..])));
+
+        // reset drops the partial frame
+        assert_eq!(frames.push(0), Ok(None));
+        frames.reset();
+        for &byte in &[0, 1, 9] {
+            assert_eq!(frames.push(byte), Ok(None));
+        }
+        assert_eq!(frames.push(9), Ok(Some(&[9][..])));
+    }
+
+    #[test]
+    fn test_frame_reader_read() {
+        let mut buffer = [0; 4];
+        let mut frames = FrameReader::<_, 1, 1>::new(&mut buffer, sum);
+
+        // an incomplete frame is kept across calls
+        let mut uart = Source(&[Ok(2), Ok(1)]);
+        assert_eq!(frames.read(&mut uart), Err(nb::Error::WouldBlock));
+        let mut uart = Source(&[Ok(2), Ok(3)]);
+        assert_eq!(frames.read(&mut uart), Ok(&[1, 2][..]));
+
+        // a read error drops the partial frame, the next one is read from its start
+        let mut uart = Source(&[
+            Ok(2),
+            Ok(1),
+            Err(ReadErrorType::Framing),
+            Ok(1),
+            Ok(5),
+            Ok(5),
+        ]);
+        assert_eq!(
+            frames.read(&mut uart),
+            Err(nb::Error::Other(FrameError::Read(ReadErrorType::Framing)))
+        );
+        assert_eq!(frames.read(&mut uart), Ok(&[5][..]));
+
+        // frame errors are reported too, and the reader starts over
+        let mut uart = Source(&[Ok(1), Ok(5), Ok(0), Ok(1), Ok(6), Ok(6)]);
+        assert_eq!(
+            frames.read(&mut uart),
+            Err(nb::Error::Other(FrameError::BadChecksum))
+        );
+        assert_eq!(frames.read(&mut uart), Ok(&[6][..]));
+    }
+
+    #[test]
+    fn test_frame_reader_read_blocking() {
+        let mut buffer = [0; 4];
+        let mut frames = FrameReader::<_, 1, 1>::new(&mut buffer, sum);
+
+        // the deadline is only checked once the UART has no more data
+        let mut uart = Source(&[Ok(1), Ok(7), Ok(7)]);
+        assert_eq!(frames.read_blocking_with(&mut uart, || true), Ok(&[7][..]));
+
+        // on timeout, the partial frame is dropped
+        let mut polls = 0;
+        let mut uart = Source(&[Ok(2), Ok(1)]);
+        let timed_out = || {
+            polls += 1;
+            polls > 3
+        };
+        assert_eq!(
+            frames.read_blocking_with(&mut uart, timed_out),
+            Err(FrameError::Timeout)
+        );
+        assert_eq!(polls, 4);
+        let mut uart = Source(&[Ok(1), Ok(8), Ok(8)]);
+        assert_eq!(frames.read_blocking_with(&mut uart, || true), Ok(&[8][..]));
+
+        // a read error drops the partial frame
+        let mut uart = Source(&[Ok(2), Err(ReadErrorType::Overrun), Ok(1), Ok(9), Ok(9)]);
+        assert_eq!(
+            frames.read_blocking_with(&mut uart, || false),
+            Err(FrameError::Read(ReadErrorType::Overrun))
+        );
+        assert_eq!(frames.read_blocking_with(&mut uart, || false), Ok(&[9][..]));
+    }
+
+    #[test]
+    fn test_frame_reader_without_checksum() {
+        let mut buffer = [0; 4];
+        let mut frames = FrameReader::<_, 1, 0>::new(&mut buffer, |_: &[u8]| []);
+
+        assert_eq!(frames.push(2), Ok(None));
+        assert_eq!(frames.push(4), Ok(None));
+        assert_eq!(frames.push(5), Ok(Some(&[4, 5][..])));
+        assert_eq!(frames.push(0), Ok(Some(&[][..])));
+    }
+}
@@ -31,16 +31,64 @@
 //!
 //! uart.write_full_blocking(b"Hello World!\r\n");
 //! ```
+//!
+//! ## Pins
+//!
+//! [`UartPeripheral::new`] takes ownership of the pins, which must be in [`FunctionUart`] and
+//! able to be routed to that UART (see [`ValidUartPinout`]): a pin left in another function, or
+//! one that only maps to the other UART, is a compile error. Pins only known at runtime can be
+//! checked with eg. [`ValidatedPinTx::validate`]. [`UartPeripheral::free`] gives the pins back.
+//!
+//! [`FunctionUart`]: crate::gpio::FunctionUart
+//!
+//! ## Flow control
+//!
+//! Hardware RTS/CTS flow control is enabled by giving the RTS and/or CTS pins along with TX and
+//! RX, as a `(tx, rx, cts, rts)` tuple: each direction is enabled if and only if its pin is
+//! routed. While CTS is deasserted, the transmitter stops and writes stall once the TX FIFO is
+//! full. RTS is deasserted when the RX FIFO is full.
+//!
+//! ## Clock
+//!
+//! Both UARTs are clocked by `clk_peri`, there is no per-UART clock selection. The frequency
+//! given to [`UartPeripheral::enable`] must therefore be the one of
+//! [`ClocksManager::peripheral_clock`], or use [`UartPeripheral::enable_with_clock`] which reads
+//! it directly.
+//!
+//! The source of `clk_peri` can be changed (eg. to the XOSC, to keep a stable baudrate when
+//! `clk_sys` is slowed down in a low power mode) with `clocks.peripheral_clock.configure_clock`.
+//! The UART doesn't track such changes: call [`UartPeripheral::reconfigure`] with the new
+//! frequency afterwards.
+//!
+//! [`ClocksManager::peripheral_clock`]: crate::clocks::ClocksManager::peripheral_clock
 
+mod coalescing;
+mod echo;
+mod frame;
+mod multidrop;
 mod peripheral;
 mod pins;
+#[cfg(feature = "heapless")]
+mod queue;
 mod reader;
+mod rs485;
+mod shared;
+#[cfg(test)]
+mod test_utils;
 mod utils;
 mod writer;
 
+pub use coalescing::CoalescingWriter;
+pub use echo::{EchoNewline, EchoUart};
+pub use frame::{FrameError, FrameReader};
+pub use multidrop::{MultidropError, MultidropReader};
 pub use peripheral::UartPeripheral;
 pub use pins::*;
-pub use reader::{ReadError, ReadErrorType, Reader};
+#[cfg(feature = "heapless")]
+pub use queue::QueuedUart;
+pub use reader::{Bytes, BytesBlocking, ReadError, ReadErrorType, Reader, ReceiveStatus};
+pub use rs485::Rs485;
+pub use shared::SharedUart;
 pub use utils::*;
 pub use writer::Writer;
 
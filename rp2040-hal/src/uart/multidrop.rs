@@ -0,0 +1,240 @@
+//! Universal Asynchronous Receiver Transmitter - Multidrop Reader
+//!
+//! On a multidrop (9-bit) bus, every byte carries an extra bit telling address bytes from data
+//! bytes. The UART has no 9th data bit, but stick parity serves the same purpose: the master
+//! sends address bytes with [`Parity::Mark`] and data bytes with [`Parity::Space`]. A node
+//! configured with [`Parity::Space`] receives the address bytes with a parity error, which marks
+//! them in the RX FIFO.
+//!
+//! [`Parity::Mark`]: super::Parity::Mark
+//! [`Parity::Space`]: super::Parity::Space
+
+use super::{ReadErrorType, Reader, UartDevice, ValidUartPinout};
+
+const FRAMING_ERROR: u16 = 1 << 8;
+const PARITY_ERROR: u16 = 1 << 9;
+const BREAK_ERROR: u16 = 1 << 10;
+const OVERRUN_ERROR: u16 = 1 << 11;
+
+/// Errors reported by a [`MultidropReader`].
+///
+/// After any of them, the current frame is discarded and the reader waits for the next address
+/// byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MultidropError {
+    /// The frame doesn't fit in the buffer.
+    Overflow,
+    /// The UART reported an error. Parity errors are never reported, as they mark the address
+    /// bytes.
+    Read(ReadErrorType),
+}
+
+#[derive(Clone, Copy)]
+enum State {
+    /// Waiting for an address byte matching ours.
+    Idle,
+    Receiving {
+        len: usize,
+    },
+}
+
+/// Receives the frames sent to one node of a multidrop bus.
+///
+/// A frame is made of an address byte followed by any number of data bytes. Frames sent to other
+/// nodes are discarded. The end of a frame is only known once the next address byte has been
+/// received, or by the protocol on top (eg. a length field or a silence on the bus): in the
+/// latter case, call [`MultidropReader::end_frame`].
+///
+/// The UART must be configured with [`Parity::Space`](super::Parity::Space). There is no address
+/// matching in hardware, every byte is still read from the FIFO, but telling address and data
+/// bytes apart doesn't require any parsing.
+///
+/// ```no_run
+/// # use rp2040_hal::{gpio::Pins, pac, sio::Sio, uart::{DataBits, MultidropReader, Parity, StopBits, UartConfig, UartPeripheral}};
+/// # use fugit::RateExtU32;
+/// # let mut peripherals = pac::Peripherals::take().unwrap();
+/// # let sio = Sio::new(peripherals.SIO);
+/// # let pins = Pins::new(peripherals.IO_BANK0, peripherals.PADS_BANK0, sio.gpio_bank0, &mut peripherals.RESETS);
+/// # let pins = (pins.gpio0.into_function(), pins.gpio1.into_function());
+/// let config = UartConfig::new(115200.Hz(), DataBits::Eight, Some(Parity::Space), StopBits::One);
+/// let uart = UartPeripheral::new(peripherals.UART0, pins, &mut peripherals.RESETS)
+///     .enable(config, 125.MHz()).unwrap();
+/// let (reader, writer) = uart.split();
+/// let mut buffer = [0; 32];
+/// let mut node = MultidropReader::new(reader, 0x12, &mut buffer);
+/// loop {
+///     match node.read() {
+///         Ok(frame) => { /* handle the frame */ }
+///         Err(nb::Error::Other(_)) => { /* a frame was dropped */ }
+///         Err(nb::Error::WouldBlock) => { /* do something else */ }
+///     }
+/// }
+/// ```
+pub struct MultidropReader<'b, D: UartDevice, P: ValidUartPinout<D>> {
+    reader: Reader<D, P>,
+    address: u8,
+    buffer: &'b mut [u8],
+    state: State,
+}
+
+impl<'b, D: UartDevice, P: ValidUartPinout<D>> MultidropReader<'b, D, P> {
+    /// Creates a new multidrop reader for the node `address`, storing frames into `buffer`.
+    pub fn new(reader: Reader<D, P>, address: u8, buffer: &'b mut [u8]) -> Self {
+        Self {
+            reader,
+            address,
+            buffer,
+            state: State::Idle,
+        }
+    }
+
+    /// Changes the address of the node.
+    ///
+    /// The frame being received, if any, is discarded.
+    pub fn set_address(&mut self, address: u8) {
+        self.address = address;
+        self.state = State::Idle;
+    }
+
+    /// The address of the node.
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// Reads the available bytes, until a frame addressed to this node is complete.
+    ///
+    /// Returns `WouldBlock` once the RX FIFO is empty and no frame is complete.
+    pub fn read(&mut self) -> nb::Result<&[u8], MultidropError> {
+        loop {
+            let word = match self.reader.read_raw_word() {
+                Ok(word) => word,
+                Err(nb::Error::WouldBlock) => return Err(nb::Error::WouldBlock),
+                Err(nb::Error::Other(e)) => match e {},
+            };
+            if let Some(len) = self.push_word(word)? {
+                return Ok(&self.buffer[..len]);
+            }
+        }
+    }
+
+    /// Ends the frame being received, returning it if it is addressed to this node.
+    ///
+    /// This is for protocols where the end of a frame is known before the next address byte is
+    /// sent.
+    pub fn end_frame(&mut self) -> Option<&[u8]> {
+        match core::mem::replace(&mut self.state, State::Idle) {
+            State::Receiving { len } => Some(&self.buffer[..len]),
+            _ => None,
+        }
+    }
+
+    /// Releases the underlying reader.
+    pub fn free(self) -> Reader<D, P> {
+        self.reader
+    }
+
+    fn push_word(&mut self, word: u16) -> Result<Option<usize>, MultidropError> {
+        push_word(&mut self.state, self.address, self.buffer, word)
+    }
+}
+
+/// Processes a word read from the RX FIFO.
+///
+/// Returns the length of the frame completed by it, if any.
+fn push_word(
+    state: &mut State,
+    address: u8,
+    buffer: &mut [u8],
+    word: u16,
+) -> Result<Option<usize>, MultidropError> {
+    let error = if word & OVERRUN_ERROR != 0 {
+        Some(ReadErrorType::Overrun)
+    } else if word & BREAK_ERROR != 0 {
+        Some(ReadErrorType::Break)
+    } else if word & FRAMING_ERROR != 0 {
+        Some(ReadErrorType::Framing)
+    } else {
+        None
+    };
+    if let Some(error) = error {
+        *state = State::Idle;
+        return Err(MultidropError::Read(error));
+    }
+
+    let byte = word as u8;
+    if word & PARITY_ERROR != 0 {
+        let done = match *state {
+            State::Receiving { len } => Some(len),
+            _ => None,
+        };
+        *state = if byte == address {
+            State::Receiving { len: 0 }
+        } else {
+            State::Idle
+        };
+        return Ok(done);
+    }
+
+    if let State::Receiving { len } = *state {
+        if len == buffer.len() {
+            *state = State::Idle;
+            return Err(MultidropError::Overflow);
+        }
+        buffer[len] = byte;
+        *state = State::Receiving { len: len + 1 };
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Node {
+        state: State,
+        buffer: [u8; 2],
+    }
+
+    impl Node {
+        fn push(&mut self, word: u16) -> Result<Option<&[u8]>, MultidropError> {
+            let done = push_word(&mut self.state, 0x12, &mut self.buffer, word)?;
+            Ok(done.map(|len| &self.buffer[..len]))
+        }
+    }
+
+    #[test]
+    fn test_push_word() {
+        let mut node = Node {
+            state: State::Idle,
+            buffer: [0; 2],
+        };
+        let address = |byte: u8| PARITY_ERROR | u16::from(byte);
+
+        // Data for another node is skipped
+        assert_eq!(node.push(address(0x34)), Ok(None));
+        assert_eq!(node.push(1), Ok(None));
+        // Our frame is complete on the next address byte
+        assert_eq!(node.push(address(0x12)), Ok(None));
+        assert_eq!(node.push(2), Ok(None));
+        assert_eq!(node.push(3), Ok(None));
+        assert_eq!(node.push(address(0x12)), Ok(Some(&[2, 3][..])));
+        // Empty frame
+        assert_eq!(node.push(address(0x34)), Ok(Some(&[][..])));
+        // Overflow, then the rest of the frame is skipped
+        assert_eq!(node.push(address(0x12)), Ok(None));
+        assert_eq!(node.push(4), Ok(None));
+        assert_eq!(node.push(5), Ok(None));
+        assert_eq!(node.push(6), Err(MultidropError::Overflow));
+        assert_eq!(node.push(7), Ok(None));
+        assert_eq!(node.push(address(0x34)), Ok(None));
+        // Errors drop the frame
+        assert_eq!(node.push(address(0x12)), Ok(None));
+        assert_eq!(
+            node.push(FRAMING_ERROR | 8),
+            Err(MultidropError::Read(ReadErrorType::Framing))
+        );
+        assert_eq!(node.push(9), Ok(None));
+        assert_eq!(node.push(address(0x34)), Ok(None));
+    }
+}
@@ -4,12 +4,15 @@
 //! UartPeripheral object that can both read and write.
 
 use core::{convert::Infallible, fmt};
+use embedded_hal::blocking::delay::DelayUs;
 use embedded_hal::serial::{Read, Write};
-use fugit::HertzU32;
+use fugit::{HertzU32, MicrosDurationU32};
 use nb::Error::{Other, WouldBlock};
 
 use crate::{
+    clocks::{Clock, PeripheralClock},
     pac::{self, uart0::uartlcr_h::W as UART_LCR_H_Writer, Peripherals, UART0, UART1},
+    timer::Timer,
     typelevel::OptionT,
     uart::*,
 };
@@ -18,17 +21,26 @@ use crate::{
 use eh_nb_1_0_alpha::serial as eh1nb;
 
 /// An UART Peripheral based on an underlying UART device.
+///
+/// It is `Send`, but not `Sync`. See [`SharedUart`] to share it with interrupt handlers.
 pub struct UartPeripheral<S: State, D: UartDevice, P: ValidUartPinout<D>> {
-    device: D,
+    pub(super) device: D,
     _state: S,
     pins: P,
+    config: UartConfig,
+    effective_baudrate: HertzU32,
 }
 
+/// Length of the break sent by [`UartPeripheral::send_break`] by default, in bit times.
+const DEFAULT_BREAK_BITS: u64 = 13;
+
 impl<S: State, D: UartDevice, P: ValidUartPinout<D>> UartPeripheral<S, D, P> {
     fn transition<To: State>(self, state: To) -> UartPeripheral<To, D, P> {
         UartPeripheral {
             device: self.device,
             pins: self.pins,
+            config: self.config,
+            effective_baudrate: self.effective_baudrate,
             _state: state,
         }
     }
@@ -49,21 +61,97 @@ impl<D: UartDevice, P: ValidUartPinout<D>> UartPeripheral<Disabled, D, P> {
             device,
             _state: Disabled,
             pins,
+            config: UartConfig::default(),
+            effective_baudrate: HertzU32::from_raw(0),
         }
     }
 
     /// Enables the provided UART device with the given configuration.
+    ///
+    /// `frequency` is the frequency of `clk_peri`, which clocks the UART.
+    ///
+    /// The baudrate actually generated may be far from the requested one when it is out of
+    /// reach of the UART clock, see [`UartPeripheral::enable_checked`] to reject it instead.
     pub fn enable(
         self,
         config: UartConfig,
         frequency: HertzU32,
     ) -> Result<UartPeripheral<Enabled, D, P>, Error> {
-        let (mut device, pins) = self.free();
-        configure_baudrate(&mut device, config.baudrate, frequency)?;
+        let divisors = calc_baud_divisors(
+            frequency.to_Hz(),
+            config.baudrate.to_Hz(),
+            config.baud_rounding,
+        )?;
+        Ok(self.apply_config(config, divisors))
+    }
 
+    /// Enables the provided UART device with the given configuration, if the requested baudrate
+    /// can be generated within ±3%.
+    ///
+    /// Otherwise, the link would not be reliable: this fails with
+    /// [`Error::BaudrateOutOfTolerance`] and hands back the untouched peripheral. To use the
+    /// achievable baudrate anyway, call [`UartPeripheral::enable`] or set it as
+    /// `config.baudrate`; [`UartConfig::achievable_baudrate`] computes it beforehand.
+    ///
+    /// ```no_run
+    /// # use rp2040_hal::{gpio::Pins, pac, sio::Sio, uart::{DataBits, Error, StopBits, UartConfig, UartPeripheral}};
+    /// # use fugit::RateExtU32;
+    /// # let mut peripherals = pac::Peripherals::take().unwrap();
+    /// # let sio = Sio::new(peripherals.SIO);
+    /// # let pins = Pins::new(peripherals.IO_BANK0, peripherals.PADS_BANK0, sio.gpio_bank0, &mut peripherals.RESETS);
+    /// # let pins = (pins.gpio0.into_function(), pins.gpio1.into_function());
+    /// let config = UartConfig::new(9600.Hz(), DataBits::Eight, None, StopBits::One);
+    /// let uart = UartPeripheral::new(peripherals.UART0, pins, &mut peripherals.RESETS);
+    /// let uart = match uart.enable_checked(config, 12.MHz()) {
+    ///     Ok(uart) => uart,
+    ///     Err((uart, Error::BaudrateOutOfTolerance { achievable })) => {
+    ///         // proceed anyway, at the closest baudrate
+    ///         uart.enable(config.baudrate(achievable.Hz()), 12.MHz()).unwrap()
+    ///     }
+    ///     Err(_) => panic!(),
+    /// };
+    /// ```
+    pub fn enable_checked(
+        self,
+        config: UartConfig,
+        frequency: HertzU32,
+    ) -> Result<UartPeripheral<Enabled, D, P>, (Self, Error)> {
+        let divisors = calc_baud_divisors(
+            frequency.to_Hz(),
+            config.baudrate.to_Hz(),
+            config.baud_rounding,
+        )
+        .and_then(|divisors| {
+            check_baud_tolerance(config.baudrate.to_Hz(), divisors.2)?;
+            Ok(divisors)
+        });
+        match divisors {
+            Ok(divisors) => Ok(self.apply_config(config, divisors)),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    fn apply_config(
+        self,
+        config: UartConfig,
+        (baud_div_int, baud_div_frac, effective_baudrate): (u16, u8, u32),
+    ) -> UartPeripheral<Enabled, D, P> {
+        let (device, pins) = self.free();
+
+        // The UART may have been left running, eg. by a previous firmware on a warm reboot.
+        // Stop it, flush the FIFOs (by disabling them) and clear any pending interrupt and
+        // receive error before applying the new configuration.
+        device.uartcr.write(|w| unsafe { w.bits(0) });
+        device.uartdmacr.write(|w| unsafe { w.bits(0) });
+        device.uartlcr_h.modify(|_, w| w.fen().clear_bit());
+        device.uarticr.write(|w| unsafe { w.bits(0x7FF) });
+        super::reader::clear_receive_status(&device);
+
+        write_baud_divisors(&device, baud_div_int, baud_div_frac);
+
+        // Writing the line control register also latches the divisors.
         device.uartlcr_h.write(|w| {
-            // FIFOs are enabled
-            w.fen().set_bit(); // Leaved here for backward compatibility
+            w.fen().bit(config.fifos);
             set_format(w, &config.data_bits, &config.stop_bits, &config.parity);
             w
         });
@@ -79,17 +167,28 @@ impl<D: UartDevice, P: ValidUartPinout<D>> UartPeripheral<Disabled, D, P> {
             w
         });
 
-        device.uartdmacr.write(|w| {
-            w.txdmae().set_bit();
-            w.rxdmae().set_bit();
-            w
-        });
+        super::writer::set_dma(&device, config.dma);
+        super::reader::set_dma(&device, config.dma);
 
-        Ok(UartPeripheral {
+        UartPeripheral {
             device,
             pins,
+            config,
+            effective_baudrate: HertzU32::from_raw(effective_baudrate),
             _state: Enabled,
-        })
+        }
+    }
+
+    /// Enables the provided UART device with the given configuration, clocked by `clock`.
+    ///
+    /// This is the same as [`UartPeripheral::enable`], reading the frequency from the clock so
+    /// it can't be mistaken for another one.
+    pub fn enable_with_clock(
+        self,
+        config: UartConfig,
+        clock: &PeripheralClock,
+    ) -> Result<UartPeripheral<Enabled, D, P>, Error> {
+        self.enable(config, clock.freq())
     }
 }
 
@@ -109,22 +208,268 @@ impl<D: UartDevice, P: ValidUartPinout<D>> UartPeripheral<Enabled, D, P> {
         self.transition(Disabled)
     }
 
+    /// Applies several configuration changes at once.
+    ///
+    /// `f` is given the current configuration and may change any of its fields. The UART is
+    /// then disabled, updated and re-enabled in a single sequence so the line never sees an
+    /// intermediate configuration. Any ongoing transmission is completed first.
+    ///
+    /// Upon success, the effective baudrate is returned. If the new configuration is rejected,
+    /// the UART keeps running with its previous configuration.
+    pub fn reconfigure(
+        &mut self,
+        f: impl FnOnce(&mut UartConfig),
+        frequency: HertzU32,
+    ) -> Result<HertzU32, Error> {
+        let mut config = self.config;
+        f(&mut config);
+
+        let (baud_div_int, baud_div_frac, effective_baudrate) = calc_baud_divisors(
+            frequency.to_Hz(),
+            config.baudrate.to_Hz(),
+            config.baud_rounding,
+        )?;
+        check_baud_tolerance(config.baudrate.to_Hz(), effective_baudrate)?;
+
+        let _ = nb::block!(super::writer::transmit_idle(&self.device));
+
+        // See Chapter 4, Section 2 §8 - UARTCR: the UART must be disabled while it is
+        // being reprogrammed.
+        let cr = self.device.uartcr.read().bits();
+        self.device.uartcr.modify(|_, w| w.uarten().clear_bit());
+
+        write_baud_divisors(&self.device, baud_div_int, baud_div_frac);
+
+        // Writing the line control register also latches the divisors.
+        self.device.uartlcr_h.write(|w| {
+            w.fen().bit(config.fifos);
+            set_format(w, &config.data_bits, &config.stop_bits, &config.parity);
+            w
+        });
+
+        self.device.uartcr.write(|w| unsafe { w.bits(cr) });
+
+        // Only touch the DMA requests when asked to, as a DMA transfer may have enabled them.
+        if config.dma != self.config.dma {
+            super::writer::set_dma(&self.device, config.dma);
+            super::reader::set_dma(&self.device, config.dma);
+        }
+
+        self.config = config;
+        self.effective_baudrate = HertzU32::from_raw(effective_baudrate);
+
+        Ok(self.effective_baudrate)
+    }
+
+    /// Changes the baudrate, keeping the data format and the enable state.
+    ///
+    /// Any ongoing transmission is completed at the old baudrate first, so the last bytes
+    /// are not mangled. This is handy to switch to a faster baudrate after a handshake.
+    ///
+    /// Upon success, the effective baudrate is returned. If the baudrate can't be generated,
+    /// the UART keeps running with the previous one.
+    ///
+    /// ```no_run
+    /// # use rp2040_hal::{gpio::Pins, pac, sio::Sio, uart::{DataBits, StopBits, UartConfig, UartPeripheral}};
+    /// # use fugit::RateExtU32;
+    /// # let mut peripherals = pac::Peripherals::take().unwrap();
+    /// # let sio = Sio::new(peripherals.SIO);
+    /// # let pins = Pins::new(peripherals.IO_BANK0, peripherals.PADS_BANK0, sio.gpio_bank0, &mut peripherals.RESETS);
+    /// # let pins = (pins.gpio0.into_function(), pins.gpio1.into_function());
+    /// let config = UartConfig::new(9600.Hz(), DataBits::Eight, None, StopBits::One);
+    /// let mut uart = UartPeripheral::new(peripherals.UART0, pins, &mut peripherals.RESETS)
+    ///     .enable(config, 125.MHz()).unwrap();
+    /// uart.write_full_blocking(b"OK");
+    /// uart.set_baudrate(921_600.Hz(), 125.MHz()).unwrap();
+    /// ```
+    pub fn set_baudrate(
+        &mut self,
+        baudrate: HertzU32,
+        frequency: HertzU32,
+    ) -> Result<HertzU32, Error> {
+        self.reconfigure(|config| config.baudrate = baudrate, frequency)
+    }
+
+    /// The configuration the UART is currently running with.
+    ///
+    /// The `baudrate` field holds the requested baudrate. The one actually generated may differ
+    /// slightly, see [`UartPeripheral::effective_baudrate`].
+    pub fn config(&self) -> &UartConfig {
+        &self.config
+    }
+
+    /// The baudrate actually generated, which may differ slightly from the requested one.
+    ///
+    /// This is also returned by [`UartPeripheral::enable`], [`UartPeripheral::set_baudrate`]
+    /// and [`UartPeripheral::reconfigure`].
+    pub fn effective_baudrate(&self) -> HertzU32 {
+        self.effective_baudrate
+    }
+
+    /// The address of the data register, to program a DMA channel writing to the TX FIFO.
+    ///
+    /// The DMA requests must be enabled with [`UartConfig::dma`], and the channel paced by
+    /// [`UartDevice::tx_dreq`].
+    pub fn tx_address(&self) -> *const u32 {
+        &self.device.uartdr as *const _ as *const u32
+    }
+
+    /// The address of the data register, to program a DMA channel reading from the RX FIFO.
+    ///
+    /// The DMA requests must be enabled with [`UartConfig::dma`], and the channel paced by
+    /// [`UartDevice::rx_dreq`].
+    pub fn rx_address(&self) -> *const u32 {
+        &self.device.uartdr as *const _ as *const u32
+    }
+
+    /// The interrupt raised by this UART, eg. to unmask it in the NVIC.
+    pub fn interrupt() -> pac::Interrupt {
+        D::INTERRUPT
+    }
+
+    /// Acknowledges the pending interrupts and returns them.
+    ///
+    /// This is meant to be called from the UART interrupt handler. The RX and RX timeout
+    /// interrupts are asserted again as long as the RX FIFO is not drained, and the TX
+    /// interrupt as long as the TX FIFO is not refilled.
+    pub fn on_interrupt(&self) -> UartInterrupts {
+        super::reader::on_interrupt(&self.device, UartInterrupts::all())
+    }
+
+    /// Unmasks the given interrupts, so they raise the UART interrupt. The other interrupts are
+    /// left untouched.
+    ///
+    /// ```no_run
+    /// # use rp2040_hal::{gpio::Pins, pac, sio::Sio, uart::{UartConfig, UartInterrupts, UartPeripheral}};
+    /// # use fugit::RateExtU32;
+    /// # let mut peripherals = pac::Peripherals::take().unwrap();
+    /// # let sio = Sio::new(peripherals.SIO);
+    /// # let pins = Pins::new(peripherals.IO_BANK0, peripherals.PADS_BANK0, sio.gpio_bank0, &mut peripherals.RESETS);
+    /// # let pins = (pins.gpio0.into_function(), pins.gpio1.into_function());
+    /// # let uart = UartPeripheral::new(peripherals.UART0, pins, &mut peripherals.RESETS)
+    /// #     .enable(UartConfig::default(), 125.MHz()).unwrap();
+    /// // Get notified of received data, including the end of a packet shorter than the watermark
+    /// uart.enable_interrupts(UartInterrupts::RX | UartInterrupts::RX_TIMEOUT);
+    /// unsafe { pac::NVIC::unmask(pac::Interrupt::UART0_IRQ) };
+    /// ```
+    pub fn enable_interrupts(&self, interrupts: UartInterrupts) {
+        super::reader::enable_interrupts(&self.device, interrupts)
+    }
+
+    /// Masks the given interrupts. The other interrupts are left untouched.
+    pub fn disable_interrupts(&self, interrupts: UartInterrupts) {
+        super::reader::disable_interrupts(&self.device, interrupts)
+    }
+
+    /// Returns the pending interrupts, among the unmasked ones.
+    pub fn interrupt_status(&self) -> UartInterrupts {
+        super::reader::interrupt_status(&self.device)
+    }
+
+    /// Acknowledges the given interrupts.
+    ///
+    /// The RX, RX timeout and TX interrupts are asserted again as long as their condition holds,
+    /// see [`UartPeripheral::on_interrupt`].
+    pub fn clear_interrupts(&self, interrupts: UartInterrupts) {
+        super::reader::clear_interrupts(&self.device, interrupts)
+    }
+
+    /// Enables/disables the internal loopback, which feeds the transmitter output to the
+    /// receiver.
+    ///
+    /// This allows a self-test without any external wiring, checking the data path and the
+    /// baudrate configuration. Any ongoing transmission is completed first. Received data only
+    /// makes it to the RX FIFO if the pinout includes an RX pin.
+    ///
+    /// ```no_run
+    /// # use rp2040_hal::{gpio::Pins, pac, sio::Sio, uart::{UartConfig, UartPeripheral}};
+    /// # use fugit::RateExtU32;
+    /// # let mut peripherals = pac::Peripherals::take().unwrap();
+    /// # let sio = Sio::new(peripherals.SIO);
+    /// # let pins = Pins::new(peripherals.IO_BANK0, peripherals.PADS_BANK0, sio.gpio_bank0, &mut peripherals.RESETS);
+    /// # let pins = (pins.gpio0.into_function(), pins.gpio1.into_function());
+    /// # let uart = UartPeripheral::new(peripherals.UART0, pins, &mut peripherals.RESETS)
+    /// #     .enable(UartConfig::default(), 125.MHz()).unwrap();
+    /// uart.set_loopback(true);
+    /// uart.write_full_blocking(&[0x55, 0xAA]);
+    /// let mut echo = [0; 2];
+    /// let passed = uart.read_full_blocking(&mut echo).is_ok() && echo == [0x55, 0xAA];
+    /// uart.set_loopback(false);
+    /// ```
+    pub fn set_loopback(&self, enabled: bool) {
+        let _ = nb::block!(super::writer::transmit_idle(&self.device));
+
+        // See Chapter 4, Section 2 §8 - UARTCR: the UART must be disabled while it is
+        // being reprogrammed.
+        let cr = self.device.uartcr.read().bits();
+        self.device.uartcr.modify(|_, w| w.uarten().clear_bit());
+        self.device
+            .uartcr
+            .write(|w| unsafe { w.bits(cr) }.lbe().bit(enabled));
+    }
+
+    /// Enables/disables the transmitter, leaving the receiver and the configuration untouched.
+    ///
+    /// Before disabling it, this blocks until any ongoing transmission is complete so the last
+    /// byte isn't cut short. Bytes written while the transmitter is disabled are queued in the TX
+    /// FIFO and sent once it is enabled again. In the meantime, [`UartPeripheral::flush`] and the
+    /// other functions waiting for the transmitter to be idle don't wait for them.
+    pub fn enable_tx(&self, enable: bool) {
+        if !enable {
+            let _ = nb::block!(super::writer::transmit_idle(&self.device));
+        }
+        self.device.uartcr.modify(|_, w| w.txe().bit(enable));
+    }
+
+    /// Enables/disables the receiver, leaving the transmitter and the configuration untouched.
+    ///
+    /// On a half-duplex bus, this keeps the UART from receiving its own transmission. Bytes
+    /// already in the RX FIFO can still be read while the receiver is disabled.
+    pub fn enable_rx(&self, enable: bool) {
+        self.device.uartcr.modify(|_, w| w.rxe().bit(enable));
+    }
+
     /// Enable/disable the rx/tx FIFO
     ///
     /// Unfortunately, it's not possible to enable/disable rx/tx
     /// independently on this chip
-    /// Default is false
+    ///
+    /// The initial state is selected by [`UartConfig::fifos`]. Disabling the FIFOs discards
+    /// their content.
     pub fn set_fifos(&mut self, enable: bool) {
-        super::reader::set_fifos(&self.device, enable)
+        super::reader::set_fifos(&self.device, enable);
+        self.config.fifos = enable;
     }
 
     /// Set rx FIFO watermark
     ///
     /// See DS: Table 423
+    ///
+    /// The RX interrupt (and RX DMA burst request) fires once the FIFO holds at least the
+    /// watermark. A low watermark reacts to every few bytes at the cost of more interrupts. A
+    /// high one batches more bytes per interrupt, the RX timeout interrupt then picking up the
+    /// tail of a message shorter than the watermark, 32 bit periods after the last byte.
     pub fn set_rx_watermark(&mut self, watermark: FifoWatermark) {
         super::reader::set_rx_watermark(&self.device, watermark)
     }
 
+    /// Enables/disables the Receive Timeout Interrupt, independently of the Receive Interrupt.
+    ///
+    /// The receive timeout fires when the RX FIFO holds data that has not been read for 32-bit
+    /// periods. When the FIFO is drained by DMA, this only causes spurious wake-ups.
+    pub fn set_rx_timeout_interrupt(&mut self, enable: bool) {
+        super::reader::set_rx_timeout_interrupt(&self.device, enable)
+    }
+
+    /// Selects whether RX DMA requests are stopped when a receive error occurs.
+    ///
+    /// See [`Reader::set_rx_dma_stop_on_error`].
+    ///
+    /// Default is false
+    pub fn set_rx_dma_stop_on_error(&mut self, enable: bool) {
+        super::reader::set_rx_dma_stop_on_error(&self.device, enable)
+    }
+
     /// Set tx FIFO watermark
     ///
     /// See DS: Table 423
@@ -175,6 +520,39 @@ impl<D: UartDevice, P: ValidUartPinout<D>> UartPeripheral<Enabled, D, P> {
         super::writer::write_raw(&self.device, data)
     }
 
+    /// Blocks until the transmitter is idle: the TX FIFO is empty and the last stop bit has left
+    /// the wire.
+    ///
+    /// Unlike [`UartPeripheral::write_full_blocking`], which returns as soon as the last byte is
+    /// in the TX FIFO, this is safe to call before turning a half-duplex transceiver around.
+    pub fn flush(&self) {
+        let _ = nb::block!(super::writer::transmit_idle(&self.device));
+    }
+
+    /// Writes bytes to the UART, advancing `data` past the bytes written.
+    ///
+    /// This function writes as long as it can, and returns `Ok(())` only once `data` is empty.
+    /// As long as bytes remain, a WouldBlock Error is returned and `data` holds them, so it can
+    /// simply be called again with the same slice:
+    ///
+    /// ```no_run
+    /// # use rp2040_hal::{gpio::Pins, pac, sio::Sio, uart::{UartConfig, UartPeripheral}};
+    /// # use fugit::RateExtU32;
+    /// # let mut peripherals = pac::Peripherals::take().unwrap();
+    /// # let sio = Sio::new(peripherals.SIO);
+    /// # let pins = Pins::new(peripherals.IO_BANK0, peripherals.PADS_BANK0, sio.gpio_bank0, &mut peripherals.RESETS);
+    /// # let pins = (pins.gpio0.into_function(), pins.gpio1.into_function());
+    /// # let uart = UartPeripheral::new(peripherals.UART0, pins, &mut peripherals.RESETS)
+    /// #     .enable(UartConfig::default(), 125.MHz()).unwrap();
+    /// let mut pending: &[u8] = b"Hello World!\r\n";
+    /// while uart.write_remaining(&mut pending).is_err() {
+    ///     // `pending.len()` bytes are left, do something else meanwhile
+    /// }
+    /// ```
+    pub fn write_remaining(&self, data: &mut &[u8]) -> nb::Result<(), Infallible> {
+        super::writer::write_remaining(&self.device, data)
+    }
+
     /// Reads bytes from the UART.
     /// This function reads as long as it can. As soon that the FIFO is empty, if :
     /// - 0 bytes were read, a WouldBlock Error is returned
@@ -196,6 +574,144 @@ impl<D: UartDevice, P: ValidUartPinout<D>> UartPeripheral<Enabled, D, P> {
         super::reader::read_full_blocking(&self.device, buffer)
     }
 
+    /// Writes bytes to the UART, calling `on_block` every time it has to wait for room in the
+    /// TX FIFO.
+    ///
+    /// This function blocks until the full buffer has been sent, but lets the caller do
+    /// something useful while waiting, eg. `cortex_m::asm::wfe()`, feeding a watchdog or
+    /// yielding to a scheduler.
+    pub fn write_full_blocking_with(&self, data: &[u8], on_block: &mut impl FnMut()) {
+        super::writer::write_full_blocking_with(&self.device, data, on_block);
+    }
+
+    /// Reads bytes from the UART, calling `on_block` every time it has to wait for data.
+    ///
+    /// This function blocks until the full buffer has been received, see
+    /// [`UartPeripheral::write_full_blocking_with`].
+    pub fn read_full_blocking_with(
+        &self,
+        buffer: &mut [u8],
+        on_block: &mut impl FnMut(),
+    ) -> Result<(), ReadErrorType> {
+        super::reader::read_full_blocking_with(&self.device, buffer, on_block)
+    }
+
+    /// Reads bytes from the UART, until the buffer is full or `timeout` has elapsed.
+    ///
+    /// Returns the number of bytes read, which is less than `buffer.len()` if the peer stopped
+    /// sending in time. The timeout covers the whole read, not the gap between two bytes.
+    ///
+    /// On a receive error, the number of valid bytes at the start of `buffer` is returned along
+    /// with the error.
+    ///
+    /// ```no_run
+    /// # use rp2040_hal::{clocks::init_clocks_and_plls, gpio::Pins, pac, sio::Sio, timer::Timer, uart::{UartConfig, UartPeripheral}, watchdog::Watchdog};
+    /// # use fugit::{ExtU32, RateExtU32};
+    /// # let mut peripherals = pac::Peripherals::take().unwrap();
+    /// # let sio = Sio::new(peripherals.SIO);
+    /// # let pins = Pins::new(peripherals.IO_BANK0, peripherals.PADS_BANK0, sio.gpio_bank0, &mut peripherals.RESETS);
+    /// # let mut watchdog = Watchdog::new(peripherals.WATCHDOG);
+    /// # let clocks = init_clocks_and_plls(12_000_000, peripherals.XOSC, peripherals.CLOCKS, peripherals.PLL_SYS, peripherals.PLL_USB, &mut peripherals.RESETS, &mut watchdog).ok().unwrap();
+    /// # let pins = (pins.gpio0.into_function(), pins.gpio1.into_function());
+    /// # let uart = UartPeripheral::new(peripherals.UART0, pins, &mut peripherals.RESETS)
+    /// #     .enable(UartConfig::default(), 125.MHz()).unwrap();
+    /// let timer = Timer::new(peripherals.TIMER, &mut peripherals.RESETS, &clocks);
+    /// let mut reply = [0; 8];
+    /// match uart.read_full_blocking_timeout(&mut reply, &timer, 100.millis()) {
+    ///     Ok(8) => { /* complete reply */ }
+    ///     Ok(n) => { /* the peer stopped after n bytes */ }
+    ///     Err((n, e)) => { /* receive error, the first n bytes are valid */ }
+    /// }
+    /// ```
+    pub fn read_full_blocking_timeout(
+        &self,
+        buffer: &mut [u8],
+        timer: &Timer,
+        timeout: MicrosDurationU32,
+    ) -> Result<usize, (usize, ReadErrorType)> {
+        super::reader::read_full_blocking_timeout(&self.device, buffer, timer, timeout)
+    }
+
+    /// Reads a single entry of the RX FIFO, without interpretation.
+    ///
+    /// The data byte is returned in bits 0 to 7, followed by the framing (8), parity (9),
+    /// break (10) and overrun (11) error flags. See Chapter 4, Section 2 §8 - Table 436:
+    /// "UARTDR Register".
+    ///
+    /// Unlike [`UartPeripheral::read_raw`], this doesn't clear the receive status.
+    pub fn read_raw_word(&self) -> nb::Result<u16, Infallible> {
+        super::reader::read_raw_word(&self.device)
+    }
+
+    /// Returns an iterator over the received bytes. See [`Bytes`].
+    ///
+    /// The iterator only borrows the UART immutably, so it can still be written to meanwhile.
+    pub fn bytes(&self) -> Bytes<'_, D> {
+        Bytes {
+            device: &self.device,
+        }
+    }
+
+    /// Returns an iterator over the received bytes, blocking for each of them. See
+    /// [`BytesBlocking`].
+    ///
+    /// The iterator only borrows the UART immutably, so it can still be written to meanwhile.
+    pub fn bytes_blocking(&self) -> BytesBlocking<'_, D> {
+        BytesBlocking {
+            device: &self.device,
+        }
+    }
+
+    /// Reads the receive error flags latched since they were last cleared.
+    pub fn read_receive_status(&self) -> ReceiveStatus {
+        super::reader::read_receive_status(&self.device)
+    }
+
+    /// Clears the latched receive error flags.
+    ///
+    /// [`UartPeripheral::read_raw`] already does this whenever it reports an error.
+    pub fn clear_receive_status(&self) {
+        super::reader::clear_receive_status(&self.device)
+    }
+
+    /// Sends a break lasting `duration`, or 13 bit times if `None`.
+    ///
+    /// 13 bit times is the minimum break of a LIN header, and much longer than any valid frame.
+    /// Any data still in the TX FIFO is sent before the break is asserted, and normal operation
+    /// resumes once it has been released.
+    pub fn send_break(&self, duration: Option<MicrosDurationU32>, delay: &mut impl DelayUs<u32>) {
+        let duration_us = match duration {
+            Some(duration) => duration.to_micros(),
+            None => self.bits_duration_us(DEFAULT_BREAK_BITS),
+        };
+        self.hold_break(duration_us, delay);
+    }
+
+    /// Sends a break lasting `chars` character times.
+    ///
+    /// The duration of a character is derived from the effective baudrate and the configured
+    /// frame format (start bit, data bits, parity bit and stop bits). Any data still in the TX
+    /// FIFO is sent before the break is asserted, and normal operation resumes once it has been
+    /// released.
+    pub fn send_break_chars(&self, chars: u8, delay: &mut impl DelayUs<u32>) {
+        let bits = u64::from(chars) * u64::from(frame_bits(&self.config));
+        self.hold_break(self.bits_duration_us(bits), delay);
+    }
+
+    /// The time taken to send `bits` bits at the effective baudrate, rounded up.
+    fn bits_duration_us(&self, bits: u64) -> u32 {
+        let baudrate = u64::from(self.effective_baudrate.to_Hz());
+        ((bits * 1_000_000 + baudrate - 1) / baudrate) as u32
+    }
+
+    fn hold_break(&self, duration_us: u32, delay: &mut impl DelayUs<u32>) {
+        let _ = nb::block!(super::writer::transmit_idle(&self.device));
+
+        self.device.uartlcr_h.modify(|_, w| w.brk().set_bit());
+        delay.delay_us(duration_us);
+        self.device.uartlcr_h.modify(|_, w| w.brk().clear_bit());
+    }
+
     /// Join the reader and writer halves together back into the original Uart peripheral.
     ///
     /// A reader/writer pair can be obtained by calling [`split`].
@@ -207,6 +723,8 @@ impl<D: UartDevice, P: ValidUartPinout<D>> UartPeripheral<Enabled, D, P> {
             device: reader.device,
             _state: Enabled,
             pins: reader.pins,
+            config: reader.config,
+            effective_baudrate: reader.effective_baudrate,
         }
     }
 }
@@ -217,6 +735,8 @@ impl<P: ValidUartPinout<UART0>> UartPeripheral<Enabled, UART0, P> {
         let reader = Reader {
             device: self.device,
             pins: self.pins,
+            config: self.config,
+            effective_baudrate: self.effective_baudrate,
         };
         // Safety: reader and writer will never write to the same address
         let device_copy = unsafe { Peripherals::steal().UART0 };
@@ -235,6 +755,8 @@ impl<P: ValidUartPinout<UART1>> UartPeripheral<Enabled, UART1, P> {
         let reader = Reader {
             device: self.device,
             pins: self.pins,
+            config: self.config,
+            effective_baudrate: self.effective_baudrate,
         };
         // Safety: reader and writer will never write to the same address
         let device_copy = unsafe { Peripherals::steal().UART1 };
@@ -250,35 +772,66 @@ impl<P: ValidUartPinout<UART1>> UartPeripheral<Enabled, UART1, P> {
 /// The PL011 (PrimeCell UART) supports a fractional baud rate divider
 /// From the wanted baudrate, we calculate the divider's two parts: integer and fractional parts.
 /// Code inspired from the C SDK.
-fn calculate_baudrate_dividers(
-    wanted_baudrate: HertzU32,
-    frequency: HertzU32,
-) -> Result<(u16, u16), Error> {
+///
+/// Returns the integer divisor (UARTIBRD), the fractional divisor (UARTFBRD) and the baudrate
+/// that those divisors actually achieve, all in Hz.
+///
+/// When the baudrate can't be generated exactly, the divisors are rounded as selected by
+/// `rounding`. Out of range baudrates are clamped to the fastest (1, 0) or slowest (65535, 0)
+/// divisors supported by the hardware.
+pub(super) fn calc_baud_divisors(
+    frequency: u32,
+    baud: u32,
+    rounding: BaudRounding,
+) -> Result<(u16, u8, u32), Error> {
     // See Chapter 4, Section 2 §7.1 from the datasheet for an explanation of how baudrate is
-    // calculated
-    let baudrate_div = frequency
-        .to_Hz()
-        .checked_mul(8)
-        .and_then(|r| r.checked_div(wanted_baudrate.to_Hz()))
-        .ok_or(Error::BadArgument)?;
-
-    Ok(match (baudrate_div >> 7, ((baudrate_div & 0x7F) + 1) / 2) {
+    // calculated.
+    // The divisor is computed in 1/64th, which is the resolution of UARTFBRD. A larger divisor
+    // means a slower baudrate.
+    let divisor = match rounding {
+        BaudRounding::Nearest => frequency
+            .checked_mul(8)
+            .and_then(|r| r.checked_div(baud))
+            .map(|r| (r + 1) / 2),
+        BaudRounding::Down => frequency
+            .checked_mul(4)
+            .and_then(|r| Some((r.checked_div(baud)?, r % baud)))
+            .map(|(q, rem)| if rem == 0 { q } else { q + 1 }),
+        BaudRounding::Up => frequency.checked_mul(4).and_then(|r| r.checked_div(baud)),
+    }
+    .ok_or(Error::BadArgument)?;
+
+    // UARTFBRD is only 6 bits wide, any carry from rounding goes into the integer part.
+    let (int_part, frac_part) = match (divisor >> 6, divisor & 0x3F) {
         (0, _) => (1, 0),
 
         (int_part, _) if int_part >= 65535 => (65535, 0),
 
-        (int_part, frac_part) => (int_part as u16, frac_part as u16),
-    })
+        (int_part, frac_part) => (int_part as u16, frac_part as u8),
+    };
+
+    let effective_baud = (4 * frequency) / (64 * int_part as u32 + frac_part as u32);
+
+    Ok((int_part, frac_part, effective_baud))
 }
 
-/// Baudrate configuration. Code loosely inspired from the C SDK.
-fn configure_baudrate<U: UartDevice>(
-    device: &mut U,
-    wanted_baudrate: HertzU32,
-    frequency: HertzU32,
-) -> Result<HertzU32, Error> {
-    let (baud_div_int, baud_div_frac) = calculate_baudrate_dividers(wanted_baudrate, frequency)?;
+/// Fails if the effective baudrate is more than 3% away from the requested one.
+///
+/// Both the sender and the receiver sample the middle of each bit: the accumulated error over a
+/// frame must stay well below half a bit for the link to be reliable.
+fn check_baud_tolerance(requested: u32, effective: u32) -> Result<(), Error> {
+    let error = u64::from(requested.abs_diff(effective));
+    if error * 100 > u64::from(requested) * 3 {
+        Err(Error::BaudrateOutOfTolerance {
+            achievable: effective,
+        })
+    } else {
+        Ok(())
+    }
+}
 
+/// Loads the baudrate divisors. They only take effect after the next write to UARTLCR_H.
+fn write_baud_divisors<U: UartDevice>(device: &U, baud_div_int: u16, baud_div_frac: u8) {
     // First we load the integer part of the divider.
     device.uartibrd.write(|w| unsafe {
         w.baud_divint().bits(baud_div_int);
@@ -287,17 +840,28 @@ fn configure_baudrate<U: UartDevice>(
 
     // Then we load the fractional part of the divider.
     device.uartfbrd.write(|w| unsafe {
-        w.baud_divfrac().bits(baud_div_frac as u8);
+        w.baud_divfrac().bits(baud_div_frac);
         w
     });
+}
 
-    // PL011 needs a (dummy) line control register write to latch in the
-    // divisors. We don't want to actually change LCR contents here.
-    device.uartlcr_h.modify(|_, w| w);
-
-    Ok(HertzU32::from_raw(
-        (4 * frequency.to_Hz()) / (64 * baud_div_int as u32 + baud_div_frac as u32),
-    ))
+/// Number of bits in a frame, including start, parity and stop bits.
+fn frame_bits(config: &UartConfig) -> u32 {
+    let data_bits = match config.data_bits {
+        DataBits::Five => 5,
+        DataBits::Six => 6,
+        DataBits::Seven => 7,
+        DataBits::Eight => 8,
+    };
+    let parity_bits = match config.parity {
+        Some(_) => 1,
+        None => 0,
+    };
+    let stop_bits = match config.stop_bits {
+        StopBits::One => 1,
+        StopBits::Two => 2,
+    };
+    1 + data_bits + parity_bits + stop_bits
 }
 
 /// Format configuration. Code loosely inspired from the C SDK.
@@ -311,12 +875,15 @@ fn set_format<'w>(
         Some(p) => {
             w.pen().set_bit();
             match p {
-                Parity::Odd => w.eps().clear_bit(),
-                Parity::Even => w.eps().set_bit(),
+                Parity::Odd => w.eps().clear_bit().sps().clear_bit(),
+                Parity::Even => w.eps().set_bit().sps().clear_bit(),
+                Parity::Mark => w.eps().clear_bit().sps().set_bit(),
+                Parity::Space => w.eps().set_bit().sps().set_bit(),
             };
         }
         None => {
             w.pen().bit(false);
+            w.sps().clear_bit();
         }
     };
 
@@ -385,7 +952,7 @@ impl<D: UartDevice, P: ValidUartPinout<D>> Write<u8> for UartPeripheral<Enabled,
     }
 
     fn flush(&mut self) -> nb::Result<(), Self::Error> {
-        super::writer::transmit_flushed(&self.device)
+        super::writer::transmit_idle(&self.device)
     }
 }
 
@@ -400,7 +967,7 @@ impl<D: UartDevice, P: ValidUartPinout<D>> eh1nb::Write<u8> for UartPeripheral<E
     }
 
     fn flush(&mut self) -> nb::Result<(), Self::Error> {
-        super::writer::transmit_flushed(&self.device).map_err(|e| match e {
+        super::writer::transmit_idle(&self.device).map_err(|e| match e {
             WouldBlock => WouldBlock,
             Other(v) => match v {},
         })
@@ -409,8 +976,79 @@ impl<D: UartDevice, P: ValidUartPinout<D>> eh1nb::Write<u8> for UartPeripheral<E
 
 impl<D: UartDevice, P: ValidUartPinout<D>> fmt::Write for UartPeripheral<Enabled, D, P> {
     fn write_str(&mut self, s: &str) -> fmt::Result {
-        s.bytes()
-            .try_for_each(|c| nb::block!(self.write(c)))
-            .map_err(|_| fmt::Error)
+        self.write_full_blocking(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calc_baud_divisors() {
+        const FREQ: u32 = 125_000_000;
+        let nearest = |baud| calc_baud_divisors(FREQ, baud, BaudRounding::Nearest);
+
+        // standard baudrates
+        assert_eq!(nearest(9600).unwrap(), (813, 51, 9600));
+        assert_eq!(nearest(19200).unwrap(), (406, 58, 19199));
+        assert_eq!(nearest(38400).unwrap(), (203, 29, 38399));
+        assert_eq!(nearest(57600).unwrap(), (135, 41, 57597));
+        assert_eq!(nearest(115200).unwrap(), (67, 52, 115207));
+        assert_eq!(nearest(230400).unwrap(), (33, 58, 230414));
+        assert_eq!(nearest(460800).unwrap(), (16, 61, 460829));
+        assert_eq!(nearest(921600).unwrap(), (8, 31, 920810));
+
+        // rounding the fractional part up to 64 carries into the integer part
+        assert_eq!(nearest(100161).unwrap(), (78, 0, 100160));
+
+        // fastest possible baudrate, and anything above it
+        assert_eq!(nearest(7_812_500).unwrap(), (1, 0, 7_812_500));
+        assert_eq!(nearest(7_812_501).unwrap(), (1, 0, 7_812_500));
+        assert_eq!(nearest(10_000_000).unwrap(), (1, 0, 7_812_500));
+
+        // slowest possible baudrate, and anything below it
+        assert_eq!(nearest(120).unwrap(), (65104, 11, 119));
+        assert_eq!(nearest(119).unwrap(), (65535, 0, 119));
+        assert_eq!(nearest(1).unwrap(), (65535, 0, 119));
+
+        // invalid arguments
+        assert!(nearest(0).is_err());
+        assert!(calc_baud_divisors(u32::MAX, 9600, BaudRounding::Nearest).is_err());
+        assert!(calc_baud_divisors(FREQ, 0, BaudRounding::Down).is_err());
+        assert!(calc_baud_divisors(u32::MAX, 9600, BaudRounding::Up).is_err());
+    }
+
+    #[test]
+    fn test_calc_baud_divisors_rounding() {
+        const FREQ: u32 = 125_000_000;
+        let down = |baud| calc_baud_divisors(FREQ, baud, BaudRounding::Down);
+        let up = |baud| calc_baud_divisors(FREQ, baud, BaudRounding::Up);
+
+        assert_eq!(down(9600).unwrap(), (813, 52, 9599));
+        assert_eq!(up(9600).unwrap(), (813, 51, 9600));
+        assert_eq!(down(115200).unwrap(), (67, 53, 115180));
+        assert_eq!(up(115200).unwrap(), (67, 52, 115207));
+        assert_eq!(down(921600).unwrap(), (8, 31, 920810));
+        assert_eq!(up(921600).unwrap(), (8, 30, 922509));
+
+        // exact divisors are not rounded
+        assert_eq!(down(7_812_500).unwrap(), (1, 0, 7_812_500));
+        assert_eq!(up(7_812_500).unwrap(), (1, 0, 7_812_500));
+    }
+
+    #[test]
+    fn test_check_baud_tolerance() {
+        assert!(check_baud_tolerance(921600, 920810).is_ok());
+        assert!(check_baud_tolerance(100, 103).is_ok());
+        assert!(check_baud_tolerance(100, 97).is_ok());
+        assert!(matches!(
+            check_baud_tolerance(10_000_000, 7_812_500),
+            Err(Error::BaudrateOutOfTolerance {
+                achievable: 7_812_500
+            })
+        ));
+        assert!(check_baud_tolerance(1, 119).is_err());
     }
 }
@@ -0,0 +1,196 @@
+//! Universal Asynchronous Receiver Transmitter - Queued UART
+//!
+//! This module moves bytes between the UART FIFOs and [`heapless::spsc`] queues from the UART
+//! interrupt handler, so the main loop can read and write without locks or busy waiting.
+//!
+//! ## Capacity
+//!
+//! A `heapless::spsc::Queue<u8, N>` holds `N - 1` bytes, on top of the 32 bytes of each hardware
+//! FIFO. The hardware FIFOs only need to cover the interrupt latency, the queues have to cover
+//! the time until the main loop comes around.
+//!
+//! ## Overflow
+//!
+//! - When the RX queue is full, received bytes are dropped and counted, see
+//!   [`QueuedUart::rx_dropped`]. Bytes received with an error are dropped too, see
+//!   [`QueuedUart::rx_errors`].
+//! - When the TX queue is full, `Producer::enqueue` fails on the main loop side, which then
+//!   decides whether to wait or to give up.
+
+use heapless::spsc::{Consumer, Producer};
+
+use super::{reader, writer, Enabled, UartDevice, UartPeripheral, ValidUartPinout};
+
+/// An enabled [`UartPeripheral`] whose received bytes are pushed into a queue and whose
+/// transmitted bytes are pulled from another, by [`QueuedUart::on_interrupt`].
+///
+/// The TX interrupt only fires when the TX FIFO drains below half full, so it doesn't fire on
+/// its own when bytes are enqueued while the UART is idle. After enqueueing, pend the UART
+/// interrupt to get the transmission going: `pac::NVIC::pend(pac::Interrupt::UART0_IRQ)`.
+///
+/// ```ignore
+/// use heapless::spsc::Queue;
+/// use rp2040_hal::{pac::{self, interrupt}, uart::QueuedUart};
+///
+/// static mut RX_QUEUE: Queue<u8, 128> = Queue::new();
+/// static mut TX_QUEUE: Queue<u8, 128> = Queue::new();
+/// static UART: Mutex<RefCell<Option<QueuedUart<'static, pac::UART0, MyPins, 128, 128>>>> = /* ... */;
+///
+/// fn main() {
+///     let uart = /* enabled UartPeripheral */;
+///     let (rx_producer, mut rx_consumer) = unsafe { RX_QUEUE.split() };
+///     let (mut tx_producer, tx_consumer) = unsafe { TX_QUEUE.split() };
+///     let mut uart = QueuedUart::new(uart);
+///     uart.attach_rx_queue(rx_producer);
+///     uart.attach_tx_queue(tx_consumer);
+///     critical_section::with(|cs| UART.borrow_ref_mut(cs).replace(uart));
+///     unsafe { pac::NVIC::unmask(pac::Interrupt::UART0_IRQ) };
+///
+///     loop {
+///         if let Some(byte) = rx_consumer.dequeue() {
+///             let _ = tx_producer.enqueue(byte);
+///             pac::NVIC::pend(pac::Interrupt::UART0_IRQ);
+///         }
+///     }
+/// }
+///
+/// #[interrupt]
+/// fn UART0_IRQ() {
+///     critical_section::with(|cs| {
+///         if let Some(uart) = UART.borrow_ref_mut(cs).as_mut() {
+///             uart.on_interrupt();
+///         }
+///     });
+/// }
+/// ```
+pub struct QueuedUart<'q, D, P, const RX: usize, const TX: usize>
+where
+    D: UartDevice,
+    P: ValidUartPinout<D>,
+{
+    uart: UartPeripheral<Enabled, D, P>,
+    rx: Option<Producer<'q, u8, RX>>,
+    tx: Option<Consumer<'q, u8, TX>>,
+    rx_dropped: u32,
+    rx_errors: u32,
+}
+
+impl<'q, D, P, const RX: usize, const TX: usize> QueuedUart<'q, D, P, RX, TX>
+where
+    D: UartDevice,
+    P: ValidUartPinout<D>,
+{
+    /// Creates a new `QueuedUart`, with no queue attached yet.
+    pub fn new(uart: UartPeripheral<Enabled, D, P>) -> Self {
+        Self {
+            uart,
+            rx: None,
+            tx: None,
+            rx_dropped: 0,
+            rx_errors: 0,
+        }
+    }
+
+    /// Pushes the received bytes into `producer`, returning the previously attached one if any.
+    ///
+    /// This enables the RX and RX timeout interrupts.
+    pub fn attach_rx_queue(
+        &mut self,
+        producer: Producer<'q, u8, RX>,
+    ) -> Option<Producer<'q, u8, RX>> {
+        reader::enable_rx_interrupt(&self.uart.device);
+        self.rx.replace(producer)
+    }
+
+    /// Stops pushing the received bytes into a queue, and disables the RX interrupts.
+    pub fn detach_rx_queue(&mut self) -> Option<Producer<'q, u8, RX>> {
+        reader::disable_rx_interrupt(&self.uart.device);
+        self.rx.take()
+    }
+
+    /// Pulls the bytes to transmit from `consumer`, returning the previously attached one if any.
+    ///
+    /// This enables the TX interrupt.
+    pub fn attach_tx_queue(
+        &mut self,
+        consumer: Consumer<'q, u8, TX>,
+    ) -> Option<Consumer<'q, u8, TX>> {
+        writer::enable_tx_interrupt(&self.uart.device);
+        self.tx.replace(consumer)
+    }
+
+    /// Stops pulling the bytes to transmit from a queue, and disables the TX interrupt.
+    ///
+    /// Bytes already in the TX FIFO are still sent.
+    pub fn detach_tx_queue(&mut self) -> Option<Consumer<'q, u8, TX>> {
+        writer::disable_tx_interrupt(&self.uart.device);
+        self.tx.take()
+    }
+
+    /// Moves bytes between the FIFOs and the queues.
+    ///
+    /// This must be called from the UART interrupt handler.
+    pub fn on_interrupt(&mut self) {
+        if let Some(rx) = &mut self.rx {
+            loop {
+                match reader::read_byte(&self.uart.device) {
+                    Ok(byte) => {
+                        if rx.enqueue(byte).is_err() {
+                            self.rx_dropped = self.rx_dropped.wrapping_add(1);
+                        }
+                    }
+                    Err(nb::Error::WouldBlock) => break,
+                    Err(nb::Error::Other(_)) => self.rx_errors = self.rx_errors.wrapping_add(1),
+                }
+            }
+        }
+
+        if let Some(tx) = &mut self.tx {
+            while writer::uart_is_writable(&self.uart.device) {
+                match tx.dequeue() {
+                    Some(byte) => {
+                        let _ = writer::write_raw(&self.uart.device, &[byte]);
+                    }
+                    None => {
+                        // Nothing left to send: the interrupt would stay pending until the FIFO
+                        // is refilled above the trigger level.
+                        self.uart
+                            .device
+                            .uarticr
+                            .write(|w| w.txic().clear_bit_by_one());
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Number of received bytes dropped because the RX queue was full.
+    pub fn rx_dropped(&self) -> u32 {
+        self.rx_dropped
+    }
+
+    /// Number of received bytes dropped because they were received with an error.
+    pub fn rx_errors(&self) -> u32 {
+        self.rx_errors
+    }
+
+    /// Gives access to the underlying UART.
+    pub fn uart(&mut self) -> &mut UartPeripheral<Enabled, D, P> {
+        &mut self.uart
+    }
+
+    /// Detaches the queues and releases the UART.
+    #[allow(clippy::type_complexity)]
+    pub fn free(
+        mut self,
+    ) -> (
+        UartPeripheral<Enabled, D, P>,
+        Option<Producer<'q, u8, RX>>,
+        Option<Consumer<'q, u8, TX>>,
+    ) {
+        let rx = self.detach_rx_queue();
+        let tx = self.detach_tx_queue();
+        (self.uart, rx, tx)
+    }
+}
@@ -2,10 +2,13 @@
 //!
 //! This module is for receiving data with a UART.
 
-use super::{FifoWatermark, UartDevice, ValidUartPinout};
+use super::{FifoWatermark, UartConfig, UartDevice, UartInterrupts, ValidUartPinout};
 use crate::dma::{EndlessReadTarget, ReadTarget};
-use crate::pac::uart0::RegisterBlock;
+use crate::pac::{self, uart0::RegisterBlock};
+use crate::timer::Timer;
+use core::convert::Infallible;
 use embedded_hal::serial::Read;
+use fugit::{HertzU32, MicrosDurationU32};
 use nb::Error::*;
 
 #[cfg(feature = "eh1_0_alpha")]
@@ -24,7 +27,7 @@ pub struct ReadError<'err> {
 
 /// Possible types of read errors. See Chapter 4, Section 2 §8 - Table 436: "UARTDR Register"
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ReadErrorType {
     /// Triggered when the FIFO (or shift-register) is overflowed.
     Overrun,
@@ -51,6 +54,55 @@ impl eh1nb::Error for ReadErrorType {
     }
 }
 
+/// Receive error flags latched in the receive status register.
+///
+/// See Chapter 4, Section 2 §8 - "UARTRSR Register"
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReceiveStatus {
+    /// Data was received while the FIFO (or shift-register) was full.
+    pub overrun: bool,
+
+    /// A break was received.
+    pub break_error: bool,
+
+    /// A character with a parity mismatch was received.
+    pub parity: bool,
+
+    /// A character without a valid stop bit was received.
+    pub framing: bool,
+}
+
+impl ReceiveStatus {
+    /// Is any of the error flags set?
+    pub fn has_error(&self) -> bool {
+        self.overrun || self.break_error || self.parity || self.framing
+    }
+}
+
+pub(crate) fn read_receive_status(rb: &RegisterBlock) -> ReceiveStatus {
+    let rsr = rb.uartrsr.read();
+    ReceiveStatus {
+        overrun: rsr.oe().bit_is_set(),
+        break_error: rsr.be().bit_is_set(),
+        parity: rsr.pe().bit_is_set(),
+        framing: rsr.fe().bit_is_set(),
+    }
+}
+
+/// Clears the receive status register.
+///
+/// The register is shared with UARTECR: writing to it clears all the flags.
+pub(crate) fn clear_receive_status(rb: &RegisterBlock) {
+    rb.uartrsr.write(|w| {
+        w.oe().clear_bit_by_one();
+        w.be().clear_bit_by_one();
+        w.pe().clear_bit_by_one();
+        w.fe().clear_bit_by_one();
+        w
+    });
+}
+
 pub(crate) fn is_readable<D: UartDevice>(device: &D) -> bool {
     device.uartfr.read().rxfe().bit_is_clear()
 }
@@ -59,7 +111,6 @@ pub(crate) fn is_readable<D: UartDevice>(device: &D) -> bool {
 ///
 /// Unfortunately, it's not possible to enable/disable rx/tx
 /// independently on this chip
-/// Default is false
 pub fn set_fifos(rb: &RegisterBlock, enable: bool) {
     if enable {
         rb.uartlcr_h.modify(|_r, w| w.fen().set_bit())
@@ -113,6 +164,55 @@ pub(crate) fn disable_rx_interrupt(rb: &RegisterBlock) {
     });
 }
 
+/// Enables/disables the Receive Timeout Interrupt on its own.
+///
+/// The receive timeout fires when the RX FIFO is non-empty but no data arrived for 32-bit
+/// periods.
+pub(crate) fn set_rx_timeout_interrupt(rb: &RegisterBlock, enable: bool) {
+    rb.uartimsc.modify(|_r, w| w.rtim().bit(enable));
+}
+
+/// Enables/disables the RX DMA requests.
+pub(crate) fn set_dma(rb: &RegisterBlock, enable: bool) {
+    rb.uartdmacr.modify(|_r, w| w.rxdmae().bit(enable));
+}
+
+/// Selects whether RX DMA requests are stopped when a receive error occurs.
+pub(crate) fn set_rx_dma_stop_on_error(rb: &RegisterBlock, enable: bool) {
+    rb.uartdmacr.modify(|_r, w| w.dmaonerr().bit(enable));
+}
+
+/// Unmasks the given interrupts, leaving the others untouched.
+pub(crate) fn enable_interrupts(rb: &RegisterBlock, interrupts: UartInterrupts) {
+    rb.uartimsc
+        .modify(|r, w| unsafe { w.bits(r.bits() | u32::from(interrupts.bits())) });
+}
+
+/// Masks the given interrupts, leaving the others untouched.
+pub(crate) fn disable_interrupts(rb: &RegisterBlock, interrupts: UartInterrupts) {
+    rb.uartimsc
+        .modify(|r, w| unsafe { w.bits(r.bits() & !u32::from(interrupts.bits())) });
+}
+
+/// Returns the pending unmasked interrupts.
+pub(crate) fn interrupt_status(rb: &RegisterBlock) -> UartInterrupts {
+    UartInterrupts::from_bits(rb.uartmis.read().bits())
+}
+
+/// Acknowledges the given interrupts.
+pub(crate) fn clear_interrupts(rb: &RegisterBlock, interrupts: UartInterrupts) {
+    rb.uarticr
+        .write(|w| unsafe { w.bits(u32::from(interrupts.bits())) });
+}
+
+/// Acknowledges the pending (unmasked) interrupts among `interrupts` and returns them.
+pub(crate) fn on_interrupt(rb: &RegisterBlock, interrupts: UartInterrupts) -> UartInterrupts {
+    let pending =
+        UartInterrupts::from_bits(u32::from(interrupt_status(rb).bits() & interrupts.bits()));
+    clear_interrupts(rb, pending);
+    pending
+}
+
 pub(crate) fn read_raw<'b, D: UartDevice>(
     device: &D,
     buffer: &'b mut [u8],
@@ -129,27 +229,25 @@ pub(crate) fn read_raw<'b, D: UartDevice>(
         }
 
         if bytes_read < buffer.len() {
-            let mut error: Option<ReadErrorType> = None;
-
             let read = device.uartdr.read();
 
-            if read.oe().bit_is_set() {
-                error = Some(ReadErrorType::Overrun);
-            }
-
-            if read.be().bit_is_set() {
-                error = Some(ReadErrorType::Break);
-            }
-
-            if read.pe().bit_is_set() {
-                error = Some(ReadErrorType::Parity);
-            }
-
-            if read.fe().bit_is_set() {
-                error = Some(ReadErrorType::Framing);
-            }
+            // A break also shows up as a framing error, and an overrun is flagged on top of
+            // whatever was wrong with the byte itself: report the most specific one.
+            let error = if read.oe().bit_is_set() {
+                Some(ReadErrorType::Overrun)
+            } else if read.be().bit_is_set() {
+                Some(ReadErrorType::Break)
+            } else if read.pe().bit_is_set() {
+                Some(ReadErrorType::Parity)
+            } else if read.fe().bit_is_set() {
+                Some(ReadErrorType::Framing)
+            } else {
+                None
+            };
 
             if let Some(err_type) = error {
+                // The error has been reported, don't let it stick to the next reads.
+                clear_receive_status(device);
                 return Err(Other(ReadError {
                     err_type,
                     discarded: &buffer[..bytes_read],
@@ -168,28 +266,155 @@ pub(crate) fn read_full_blocking<D: UartDevice>(
     device: &D,
     buffer: &mut [u8],
 ) -> Result<(), ReadErrorType> {
+    read_full_blocking_with(device, buffer, &mut || {})
+}
+
+/// Reads bytes from the UART, calling `on_block` every time the RX FIFO is empty.
+pub(crate) fn read_full_blocking_with<D: UartDevice>(
+    device: &D,
+    buffer: &mut [u8],
+    on_block: &mut impl FnMut(),
+) -> Result<(), ReadErrorType> {
+    read_full_blocking_until(device, buffer, || {
+        on_block();
+        false
+    })
+    .map(|_| ())
+    .map_err(|(_, e)| e)
+}
+
+/// Reads bytes from the UART until `buffer` is full or `timeout` has elapsed.
+///
+/// Returns the number of bytes read, which is less than `buffer.len()` on timeout.
+pub(crate) fn read_full_blocking_timeout<D: UartDevice>(
+    device: &D,
+    buffer: &mut [u8],
+    timer: &Timer,
+    timeout: MicrosDurationU32,
+) -> Result<usize, (usize, ReadErrorType)> {
+    let deadline = timer.get_counter() + timeout;
+    read_full_blocking_until(device, buffer, || timer.get_counter() >= deadline)
+}
+
+/// Reads bytes from the UART until `buffer` is full, or `give_up` returns true while the RX
+/// FIFO is empty.
+///
+/// Returns the number of bytes read. On error, it is returned along with the error: the bytes
+/// received before the faulty one are valid.
+fn read_full_blocking_until<D: UartDevice>(
+    device: &D,
+    buffer: &mut [u8],
+    mut give_up: impl FnMut() -> bool,
+) -> Result<usize, (usize, ReadErrorType)> {
     let mut offset = 0;
 
     while offset != buffer.len() {
         offset += match read_raw(device, &mut buffer[offset..]) {
             Ok(bytes_read) => bytes_read,
-            Err(e) => match e {
-                Other(inner) => return Err(inner.err_type),
-                WouldBlock => continue,
-            },
+            Err(Other(inner)) => return Err((offset + inner.discarded.len(), inner.err_type)),
+            Err(WouldBlock) => {
+                if give_up() {
+                    break;
+                }
+                continue;
+            }
         }
     }
 
-    Ok(())
+    Ok(offset)
+}
+
+pub(crate) fn read_raw_word<D: UartDevice>(device: &D) -> nb::Result<u16, Infallible> {
+    if !is_readable(device) {
+        return Err(WouldBlock);
+    }
+
+    Ok((device.uartdr.read().bits() & 0xFFF) as u16)
+}
+
+pub(crate) fn read_byte<D: UartDevice>(device: &D) -> nb::Result<u8, ReadErrorType> {
+    let byte: &mut [u8] = &mut [0; 1];
+
+    match read_raw(device, byte) {
+        Ok(_) => Ok(byte[0]),
+        Err(e) => match e {
+            Other(inner) => Err(Other(inner.err_type)),
+            WouldBlock => Err(WouldBlock),
+        },
+    }
+}
+
+/// Iterator over the bytes received by a UART. Obtained by calling `bytes()` on a
+/// [`UartPeripheral`] or a [`Reader`].
+///
+/// Each item is the result of a non-blocking read: `WouldBlock` is yielded while no data is
+/// available. The iterator never ends.
+///
+/// [`UartPeripheral`]: super::UartPeripheral
+pub struct Bytes<'u, D: UartDevice> {
+    pub(super) device: &'u D,
+}
+
+impl<'u, D: UartDevice> Iterator for Bytes<'u, D> {
+    type Item = nb::Result<u8, ReadErrorType>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(read_byte(self.device))
+    }
+}
+
+/// Iterator over the bytes received by a UART, blocking until each of them arrives. Obtained by
+/// calling `bytes_blocking()` on a [`UartPeripheral`] or a [`Reader`].
+///
+/// The iterator never ends, use adapters such as [`Iterator::take`] or
+/// [`Iterator::take_while`] to stop after a given length or terminator.
+///
+/// ```no_run
+/// # use rp2040_hal::{gpio::Pins, pac, sio::Sio, uart::{UartConfig, UartPeripheral}};
+/// # use fugit::RateExtU32;
+/// # let mut peripherals = pac::Peripherals::take().unwrap();
+/// # let sio = Sio::new(peripherals.SIO);
+/// # let pins = Pins::new(peripherals.IO_BANK0, peripherals.PADS_BANK0, sio.gpio_bank0, &mut peripherals.RESETS);
+/// # let pins = (pins.gpio0.into_function(), pins.gpio1.into_function());
+/// # let uart = UartPeripheral::new(peripherals.UART0, pins, &mut peripherals.RESETS)
+/// #     .enable(UartConfig::default(), 125.MHz()).unwrap();
+/// let mut line = [0; 64];
+/// let mut len = 0;
+/// for byte in uart.bytes_blocking().map_while(Result::ok).take_while(|&b| b != b'\n') {
+///     if len < line.len() {
+///         line[len] = byte;
+///         len += 1;
+///     }
+/// }
+/// uart.write_full_blocking(&line[..len]);
+/// ```
+///
+/// [`UartPeripheral`]: super::UartPeripheral
+pub struct BytesBlocking<'u, D: UartDevice> {
+    pub(super) device: &'u D,
+}
+
+impl<'u, D: UartDevice> Iterator for BytesBlocking<'u, D> {
+    type Item = Result<u8, ReadErrorType>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(nb::block!(read_byte(self.device)))
+    }
 }
 
 /// Half of an [`UartPeripheral`] that is only capable of reading. Obtained by calling [`UartPeripheral::split()`]
 ///
+/// It can be used as the source of a DMA transfer. When streaming continuously with DMA, the
+/// CPU doesn't need to be woken up by the receive timeout: if the UART interrupt is used for
+/// something else (eg. errors), disable it with [`Reader::set_rx_timeout_interrupt`].
+///
 /// [`UartPeripheral`]: struct.UartPeripheral.html
 /// [`UartPeripheral::split()`]: struct.UartPeripheral.html#method.split
 pub struct Reader<D: UartDevice, P: ValidUartPinout<D>> {
     pub(super) device: D,
     pub(super) pins: P,
+    pub(super) config: UartConfig,
+    pub(super) effective_baudrate: HertzU32,
 }
 
 impl<D: UartDevice, P: ValidUartPinout<D>> Reader<D, P> {
@@ -208,6 +433,113 @@ impl<D: UartDevice, P: ValidUartPinout<D>> Reader<D, P> {
         read_full_blocking(&self.device, buffer)
     }
 
+    /// Reads bytes from the UART, calling `on_block` every time it has to wait for data.
+    ///
+    /// See [`UartPeripheral::read_full_blocking_with`].
+    ///
+    /// [`UartPeripheral::read_full_blocking_with`]: super::UartPeripheral::read_full_blocking_with
+    pub fn read_full_blocking_with(
+        &self,
+        buffer: &mut [u8],
+        on_block: &mut impl FnMut(),
+    ) -> Result<(), ReadErrorType> {
+        read_full_blocking_with(&self.device, buffer, on_block)
+    }
+
+    /// Reads bytes from the UART, until the buffer is full or `timeout` has elapsed.
+    ///
+    /// See [`UartPeripheral::read_full_blocking_timeout`].
+    ///
+    /// [`UartPeripheral::read_full_blocking_timeout`]: super::UartPeripheral::read_full_blocking_timeout
+    pub fn read_full_blocking_timeout(
+        &self,
+        buffer: &mut [u8],
+        timer: &Timer,
+        timeout: MicrosDurationU32,
+    ) -> Result<usize, (usize, ReadErrorType)> {
+        read_full_blocking_timeout(&self.device, buffer, timer, timeout)
+    }
+
+    /// Reads a single entry of the RX FIFO, without interpretation.
+    ///
+    /// See [`UartPeripheral::read_raw_word`].
+    ///
+    /// [`UartPeripheral::read_raw_word`]: super::UartPeripheral::read_raw_word
+    pub fn read_raw_word(&self) -> nb::Result<u16, Infallible> {
+        read_raw_word(&self.device)
+    }
+
+    /// Returns an iterator over the received bytes. See [`Bytes`].
+    pub fn bytes(&self) -> Bytes<'_, D> {
+        Bytes {
+            device: &self.device,
+        }
+    }
+
+    /// Returns an iterator over the received bytes, blocking for each of them. See
+    /// [`BytesBlocking`].
+    pub fn bytes_blocking(&self) -> BytesBlocking<'_, D> {
+        BytesBlocking {
+            device: &self.device,
+        }
+    }
+
+    /// Reads the receive error flags latched since they were last cleared.
+    pub fn read_receive_status(&self) -> ReceiveStatus {
+        read_receive_status(&self.device)
+    }
+
+    /// Clears the latched receive error flags.
+    ///
+    /// [`Reader::read_raw`] already does this whenever it reports an error.
+    pub fn clear_receive_status(&self) {
+        clear_receive_status(&self.device)
+    }
+
+    /// The interrupt raised by this UART.
+    pub fn interrupt() -> pac::Interrupt {
+        D::INTERRUPT
+    }
+
+    /// Acknowledges the pending receive interrupts and returns them.
+    ///
+    /// Only the RX, RX timeout and receive error interrupts are handled: the TX and CTS ones
+    /// belong to the [`Writer`](super::Writer) half and are left pending. See
+    /// [`UartPeripheral::on_interrupt`].
+    ///
+    /// [`UartPeripheral::on_interrupt`]: super::UartPeripheral::on_interrupt
+    pub fn on_interrupt(&self) -> UartInterrupts {
+        on_interrupt(&self.device, UartInterrupts::RECEIVE)
+    }
+
+    /// Unmasks the given interrupts.
+    ///
+    /// See [`UartPeripheral::enable_interrupts`].
+    ///
+    /// [`UartPeripheral::enable_interrupts`]: super::UartPeripheral::enable_interrupts
+    pub fn enable_interrupts(&self, interrupts: UartInterrupts) {
+        enable_interrupts(&self.device, interrupts)
+    }
+
+    /// Masks the given interrupts.
+    pub fn disable_interrupts(&self, interrupts: UartInterrupts) {
+        disable_interrupts(&self.device, interrupts)
+    }
+
+    /// Returns the pending interrupts, among the unmasked ones.
+    pub fn interrupt_status(&self) -> UartInterrupts {
+        interrupt_status(&self.device)
+    }
+
+    /// Acknowledges the given interrupts.
+    ///
+    /// See [`UartPeripheral::clear_interrupts`].
+    ///
+    /// [`UartPeripheral::clear_interrupts`]: super::UartPeripheral::clear_interrupts
+    pub fn clear_interrupts(&self, interrupts: UartInterrupts) {
+        clear_interrupts(&self.device, interrupts)
+    }
+
     /// Enables the Receive Interrupt.
     ///
     /// The relevant UARTx IRQ will fire when there is data in the receive register.
@@ -219,21 +551,41 @@ impl<D: UartDevice, P: ValidUartPinout<D>> Reader<D, P> {
     pub fn disable_rx_interrupt(&mut self) {
         disable_rx_interrupt(&self.device)
     }
+
+    /// Enables/disables the Receive Timeout Interrupt, independently of the Receive Interrupt.
+    ///
+    /// The receive timeout fires when the RX FIFO holds data that has not been read for 32-bit
+    /// periods. When the FIFO is drained by DMA, this only causes spurious wake-ups.
+    pub fn set_rx_timeout_interrupt(&mut self, enable: bool) {
+        set_rx_timeout_interrupt(&self.device, enable)
+    }
+
+    /// Set rx FIFO watermark
+    ///
+    /// This is the level at which the Receive Interrupt fires.
+    ///
+    /// See DS: Table 423
+    pub fn set_rx_watermark(&mut self, watermark: FifoWatermark) {
+        set_rx_watermark(&self.device, watermark)
+    }
+
+    /// Selects whether RX DMA requests are stopped when a receive error occurs.
+    ///
+    /// When enabled, DMA requests are held off while the error interrupt is asserted, so the
+    /// erroneous data is not silently streamed into memory. The error interrupt must then be
+    /// cleared to resume the transfer.
+    ///
+    /// Default is false
+    pub fn set_rx_dma_stop_on_error(&mut self, enable: bool) {
+        set_rx_dma_stop_on_error(&self.device, enable)
+    }
 }
 
 impl<D: UartDevice, P: ValidUartPinout<D>> Read<u8> for Reader<D, P> {
     type Error = ReadErrorType;
 
     fn read(&mut self) -> nb::Result<u8, Self::Error> {
-        let byte: &mut [u8] = &mut [0; 1];
-
-        match self.read_raw(byte) {
-            Ok(_) => Ok(byte[0]),
-            Err(e) => match e {
-                Other(inner) => Err(Other(inner.err_type)),
-                WouldBlock => Err(WouldBlock),
-            },
-        }
+        read_byte(&self.device)
     }
 }
 
@@ -247,6 +599,7 @@ unsafe impl<D: UartDevice, P: ValidUartPinout<D>> ReadTarget for Reader<D, P> {
     }
 
     fn rx_address_count(&self) -> (u32, u32) {
+        set_dma(&self.device, true);
         (&self.device.uartdr as *const _ as u32, u32::MAX)
     }
 
@@ -0,0 +1,132 @@
+//! Universal Asynchronous Receiver Transmitter - RS-485 half-duplex support
+//!
+//! RS-485 transceivers need a driver-enable (DE) pin to be asserted while transmitting and
+//! released once the last bit has left the wire. This module wraps an enabled
+//! [`UartPeripheral`] together with such a pin and takes care of the timing.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use rp2040_hal::{Clock, clocks::init_clocks_and_plls, gpio::Pins, pac, sio::Sio, timer::Timer, uart::{DataBits, Rs485, StopBits, UartConfig, UartPeripheral}, watchdog::Watchdog};
+//! use fugit::{ExtU32, RateExtU32};
+//!
+//! const XOSC_CRYSTAL_FREQ: u32 = 12_000_000; // Typically found in BSP crates
+//!
+//! let mut peripherals = pac::Peripherals::take().unwrap();
+//! let sio = Sio::new(peripherals.SIO);
+//! let pins = Pins::new(peripherals.IO_BANK0, peripherals.PADS_BANK0, sio.gpio_bank0, &mut peripherals.RESETS);
+//! let mut watchdog = Watchdog::new(peripherals.WATCHDOG);
+//! let mut clocks = init_clocks_and_plls(XOSC_CRYSTAL_FREQ, peripherals.XOSC, peripherals.CLOCKS, peripherals.PLL_SYS, peripherals.PLL_USB, &mut peripherals.RESETS, &mut watchdog).ok().unwrap();
+//! let timer = Timer::new(peripherals.TIMER, &mut peripherals.RESETS, &clocks);
+//!
+//! let uart_pins = (
+//!     pins.gpio0.into_function(),
+//!     pins.gpio1.into_function(),
+//! );
+//! let uart = UartPeripheral::new(peripherals.UART0, uart_pins, &mut peripherals.RESETS)
+//!     .enable(
+//!         UartConfig::new(9600.Hz(), DataBits::Eight, None, StopBits::One),
+//!         clocks.peripheral_clock.freq(),
+//!     ).unwrap();
+//!
+//! let mut rs485 = Rs485::new(uart, pins.gpio2.into_push_pull_output(), timer).unwrap();
+//! rs485.set_hold_time(10.micros());
+//! rs485.write_full_blocking(b"Hello RS-485!\r\n").unwrap();
+//! ```
+
+use embedded_hal::blocking::delay::DelayUs;
+use embedded_hal::digital::v2::OutputPin;
+use fugit::MicrosDurationU32;
+
+use super::{Enabled, ReadError, ReadErrorType, UartDevice, UartPeripheral, ValidUartPinout};
+
+/// An RS-485 half-duplex driver built on top of an enabled [`UartPeripheral`].
+///
+/// The driver-enable pin is asserted (driven high) for the duration of each write, and only
+/// released once the transmitter is idle and the configured hold time has elapsed. The receiver
+/// is disabled while transmitting so the local echo of the transmitted bytes is not read back.
+pub struct Rs485<D, P, DE, DELAY>
+where
+    D: UartDevice,
+    P: ValidUartPinout<D>,
+    DE: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    uart: UartPeripheral<Enabled, D, P>,
+    de: DE,
+    delay: DELAY,
+    hold_time: MicrosDurationU32,
+}
+
+impl<D, P, DE, DELAY> Rs485<D, P, DE, DELAY>
+where
+    D: UartDevice,
+    P: ValidUartPinout<D>,
+    DE: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    /// Creates a new RS-485 driver, releasing the driver-enable pin.
+    ///
+    /// The hold time defaults to zero.
+    pub fn new(
+        uart: UartPeripheral<Enabled, D, P>,
+        mut de: DE,
+        delay: DELAY,
+    ) -> Result<Self, DE::Error> {
+        de.set_low()?;
+
+        Ok(Self {
+            uart,
+            de,
+            delay,
+            hold_time: MicrosDurationU32::micros(0),
+        })
+    }
+
+    /// Sets how long the driver-enable pin stays asserted after the last stop bit was sent.
+    pub fn set_hold_time(&mut self, hold_time: MicrosDurationU32) {
+        self.hold_time = hold_time;
+    }
+
+    /// Writes bytes to the bus.
+    ///
+    /// This function asserts the driver-enable pin, blocks until the full buffer has been sent
+    /// and the transmitter is idle, waits for the hold time and then releases the pin.
+    pub fn write_full_blocking(&mut self, data: &[u8]) -> Result<(), DE::Error> {
+        self.de.set_high()?;
+
+        let rx_enabled = self.uart.device.uartcr.read().rxe().bit_is_set();
+        self.uart.enable_rx(false);
+
+        self.uart.write_full_blocking(data);
+        self.uart.flush();
+
+        let hold_time = self.hold_time.to_micros();
+        if hold_time > 0 {
+            self.delay.delay_us(hold_time);
+        }
+        let result = self.de.set_low();
+
+        self.uart.enable_rx(rx_enabled);
+
+        result
+    }
+
+    /// Reads bytes from the bus.
+    ///
+    /// See [`UartPeripheral::read_raw`].
+    pub fn read_raw<'b>(&self, buffer: &'b mut [u8]) -> nb::Result<usize, ReadError<'b>> {
+        self.uart.read_raw(buffer)
+    }
+
+    /// Reads bytes from the bus.
+    /// This function blocks until the full buffer has been received.
+    pub fn read_full_blocking(&self, buffer: &mut [u8]) -> Result<(), ReadErrorType> {
+        self.uart.read_full_blocking(buffer)
+    }
+
+    /// Releases the underlying UART, driver-enable pin and delay provider.
+    pub fn free(self) -> (UartPeripheral<Enabled, D, P>, DE, DELAY) {
+        (self.uart, self.de, self.delay)
+    }
+}
@@ -0,0 +1,78 @@
+//! Universal Asynchronous Receiver Transmitter - Shared access
+//!
+//! [`UartPeripheral`], [`Reader`] and [`Writer`] are `Send` (as long as their pins are), so they
+//! can be moved into a `static` protected by a [`critical_section::Mutex`] and used from both the
+//! main loop and interrupt handlers. They are not `Sync`: concurrent access must go through such a
+//! lock. [`SharedUart`] packs that boilerplate.
+//!
+//! ```ignore
+//! use rp2040_hal::{pac::{self, interrupt}, uart::{Enabled, SharedUart, UartPeripheral}};
+//!
+//! type Uart = UartPeripheral<Enabled, pac::UART0, MyPins>;
+//! static UART: SharedUart<pac::UART0, MyPins> = SharedUart::new();
+//!
+//! fn main() {
+//!     let uart: Uart = /* ... */;
+//!     UART.init(uart);
+//!     UART.with(|uart| uart.write_full_blocking(b"Hello from main\r\n"));
+//! }
+//!
+//! #[interrupt]
+//! fn UART0_IRQ() {
+//!     UART.with(|uart| {
+//!         let mut buffer = [0; 32];
+//!         let _ = uart.read_raw(&mut buffer);
+//!     });
+//! }
+//! ```
+//!
+//! [`Reader`]: super::Reader
+//! [`Writer`]: super::Writer
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+
+use super::{Enabled, UartDevice, UartPeripheral, ValidUartPinout};
+
+/// An enabled [`UartPeripheral`] that can be shared between the main loop and interrupt handlers.
+///
+/// Every access runs inside a critical section.
+pub struct SharedUart<D: UartDevice, P: ValidUartPinout<D>> {
+    uart: Mutex<RefCell<Option<UartPeripheral<Enabled, D, P>>>>,
+}
+
+impl<D: UartDevice, P: ValidUartPinout<D>> SharedUart<D, P> {
+    /// Creates an empty `SharedUart`, suitable for initializing a `static`.
+    pub const fn new() -> Self {
+        Self {
+            uart: Mutex::new(RefCell::new(None)),
+        }
+    }
+
+    /// Stores the UART, returning the previously stored one if any.
+    pub fn init(
+        &self,
+        uart: UartPeripheral<Enabled, D, P>,
+    ) -> Option<UartPeripheral<Enabled, D, P>> {
+        critical_section::with(|cs| self.uart.borrow_ref_mut(cs).replace(uart))
+    }
+
+    /// Removes the UART, leaving the `SharedUart` empty.
+    pub fn take(&self) -> Option<UartPeripheral<Enabled, D, P>> {
+        critical_section::with(|cs| self.uart.borrow_ref_mut(cs).take())
+    }
+
+    /// Runs `f` on the UART from within a critical section.
+    ///
+    /// Returns `None` without calling `f` if no UART has been stored.
+    pub fn with<R>(&self, f: impl FnOnce(&mut UartPeripheral<Enabled, D, P>) -> R) -> Option<R> {
+        critical_section::with(|cs| self.uart.borrow_ref_mut(cs).as_mut().map(f))
+    }
+}
+
+impl<D: UartDevice, P: ValidUartPinout<D>> Default for SharedUart<D, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,45 @@
+//! Fixtures shared by the host tests of the UART helpers.
+
+use core::convert::Infallible;
+use embedded_hal::serial::{Read, Write};
+
+/// A serial reader replaying received bytes and errors, then reporting `WouldBlock`.
+pub(super) struct Source<'a, E>(pub(super) &'a [Result<u8, E>]);
+
+impl<'a, E: Copy> Read<u8> for Source<'a, E> {
+    type Error = E;
+
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        let (&item, rest) = self.0.split_first().ok_or(nb::Error::WouldBlock)?;
+        self.0 = rest;
+        item.map_err(nb::Error::Other)
+    }
+}
+
+/// A serial writer recording the bytes written to it.
+#[derive(Default)]
+pub(super) struct Sink {
+    data: [u8; 32],
+    len: usize,
+}
+
+impl Sink {
+    /// The bytes written so far.
+    pub(super) fn written(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+impl Write<u8> for Sink {
+    type Error = Infallible;
+
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        self.data[self.len] = word;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        Ok(())
+    }
+}
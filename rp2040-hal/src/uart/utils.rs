@@ -1,16 +1,24 @@
 use crate::pac::dma::ch::ch_ctrl_trig::TREQ_SEL_A;
-use crate::pac::{uart0::RegisterBlock, UART0, UART1};
+use crate::pac::{self, uart0::RegisterBlock, UART0, UART1};
 use crate::resets::SubsystemReset;
 use crate::typelevel::Sealed;
-use core::ops::Deref;
+use core::ops::{BitOr, BitOrAssign, Deref};
 use fugit::HertzU32;
 
 /// Error type for UART operations.
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
 pub enum Error {
     /// Bad argument : when things overflow, ...
     BadArgument,
+    /// The requested baudrate can't be generated within ±3% from the UART clock.
+    ///
+    /// `achievable` is the closest baudrate that can be generated, in Hz.
+    BaudrateOutOfTolerance {
+        /// The closest baudrate that can be generated, in Hz.
+        achievable: u32,
+    },
 }
 /// State of the UART Peripheral.
 pub trait State: Sealed {}
@@ -20,6 +28,9 @@ pub trait UartDevice: Deref<Target = RegisterBlock> + SubsystemReset + Sealed +
     /// Index of the Uart.
     const ID: usize;
 
+    /// The interrupt raised by this Uart.
+    const INTERRUPT: pac::Interrupt;
+
     /// The DREQ number for which TX DMA requests are triggered.
     fn tx_dreq() -> u8
     where
@@ -32,6 +43,7 @@ pub trait UartDevice: Deref<Target = RegisterBlock> + SubsystemReset + Sealed +
 
 impl UartDevice for UART0 {
     const ID: usize = 0;
+    const INTERRUPT: pac::Interrupt = pac::Interrupt::UART0_IRQ;
 
     /// The DREQ number for which TX DMA requests are triggered.
     fn tx_dreq() -> u8 {
@@ -45,6 +57,7 @@ impl UartDevice for UART0 {
 impl Sealed for UART0 {}
 impl UartDevice for UART1 {
     const ID: usize = 1;
+    const INTERRUPT: pac::Interrupt = pac::Interrupt::UART1_IRQ;
 
     /// The DREQ number for which TX DMA requests are triggered.
     fn tx_dreq() -> u8 {
@@ -69,6 +82,7 @@ impl State for Disabled {}
 impl Sealed for Disabled {}
 
 /// Data bits
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DataBits {
     /// 5 bits
     Five,
@@ -81,6 +95,7 @@ pub enum DataBits {
 }
 
 /// Stop bits
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum StopBits {
     /// 1 bit
     One,
@@ -90,15 +105,50 @@ pub enum StopBits {
 
 /// Parity
 /// The "none" state of parity is represented with the Option type (None).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Parity {
     /// Odd parity
     Odd,
     /// Even parity
     Even,
+    /// Stick parity: the parity bit is always 1, and received with a parity error if it is 0.
+    ///
+    /// Together with [`Parity::Space`], this is used to tell address and data bytes apart on
+    /// multidrop buses, see [`MultidropReader`](super::MultidropReader).
+    Mark,
+    /// Stick parity: the parity bit is always 0, and received with a parity error if it is 1.
+    Space,
+}
+
+/// Rounding of the baudrate divisors, when the requested baudrate can't be generated exactly.
+///
+/// The error is usually well within the tolerance of the receiver, but close to its limits it
+/// can matter that the generated baudrate is slower or faster than the one of the peer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BaudRounding {
+    /// Generate the baudrate closest to the requested one.
+    #[default]
+    Nearest,
+    /// Never generate a baudrate faster than the requested one.
+    Down,
+    /// Never generate a baudrate slower than the requested one.
+    Up,
 }
 
 /// A struct holding the configuration for an UART device.
 ///
+/// It is built with [`UartConfig::new`] or from [`UartConfig::default`], then adjusted with the
+/// builder methods:
+/// ```
+/// # use rp2040_hal::uart::{DataBits, Parity, StopBits, UartConfig};
+/// # use fugit::RateExtU32;
+/// let dmx = UartConfig::default()
+///     .baudrate(250_000.Hz())
+///     .parity(Some(Parity::Even))
+///     .stop_bits(StopBits::Two);
+/// assert_eq!(dmx.data_bits, DataBits::Eight);
+/// ```
+///
 /// The `Default` implementation implements the following values:
 /// ```ignore
 /// # // can't actually create this with the non_exhaustive attribute
@@ -107,8 +157,12 @@ pub enum Parity {
 ///    data_bits: DataBits::Eight,
 ///    stop_bits: StopBits::One,
 ///    parity: None,
+///    baud_rounding: BaudRounding::Nearest,
+///    fifos: true,
+///    dma: false,
 ///}
 /// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub struct UartConfig {
     /// The baudrate the uart will run at.
@@ -122,6 +176,23 @@ pub struct UartConfig {
 
     /// The parity that this uart should have
     pub parity: Option<Parity>,
+
+    /// How the baudrate is rounded when it can't be generated exactly.
+    pub baud_rounding: BaudRounding,
+
+    /// Whether the 32 byte RX and TX FIFOs are enabled.
+    ///
+    /// Without them, the UART holds a single byte in each direction: the RX interrupt fires for
+    /// every byte received and the TX interrupt for every byte sent, which lowers the latency to
+    /// the bare minimum but leaves no room for a late interrupt handler.
+    pub fifos: bool,
+
+    /// Whether the TX and RX DMA requests are enabled.
+    ///
+    /// The DMA targets implemented by [`Reader`](super::Reader) and [`Writer`](super::Writer)
+    /// enable their request when a transfer is set up, so this is only needed to program a DMA
+    /// channel by hand, see [`UartPeripheral::tx_address`](super::UartPeripheral::tx_address).
+    pub dma: bool,
 }
 
 impl UartConfig {
@@ -137,16 +208,82 @@ impl UartConfig {
             data_bits,
             stop_bits,
             parity,
+            baud_rounding: BaudRounding::Nearest,
+            fifos: true,
+            dma: false,
         }
     }
+
+    /// Set the baudrate.
+    pub const fn baudrate(mut self, baudrate: HertzU32) -> Self {
+        self.baudrate = baudrate;
+        self
+    }
+
+    /// Set the amount of data bits.
+    pub const fn data_bits(mut self, data_bits: DataBits) -> Self {
+        self.data_bits = data_bits;
+        self
+    }
+
+    /// Set the amount of stop bits.
+    pub const fn stop_bits(mut self, stop_bits: StopBits) -> Self {
+        self.stop_bits = stop_bits;
+        self
+    }
+
+    /// Set the parity, `None` to disable it.
+    pub const fn parity(mut self, parity: Option<Parity>) -> Self {
+        self.parity = parity;
+        self
+    }
+
+    /// Set how the baudrate is rounded when it can't be generated exactly.
+    ///
+    /// Default is [`BaudRounding::Nearest`]
+    pub const fn baud_rounding(mut self, baud_rounding: BaudRounding) -> Self {
+        self.baud_rounding = baud_rounding;
+        self
+    }
+
+    /// Enable or disable the RX and TX FIFOs.
+    ///
+    /// Default is true
+    pub const fn fifos(mut self, fifos: bool) -> Self {
+        self.fifos = fifos;
+        self
+    }
+
+    /// Enable or disable the TX and RX DMA requests.
+    ///
+    /// Default is false
+    pub const fn dma(mut self, dma: bool) -> Self {
+        self.dma = dma;
+        self
+    }
+
+    /// The baudrate actually generated for this configuration from a UART clock of `frequency`.
+    ///
+    /// Unlike [`UartPeripheral::enable`](super::UartPeripheral::enable), this doesn't check
+    /// that it's within the tolerance of the requested baudrate.
+    pub fn achievable_baudrate(&self, frequency: HertzU32) -> Result<HertzU32, Error> {
+        let (_, _, baudrate) = super::peripheral::calc_baud_divisors(
+            frequency.to_Hz(),
+            self.baudrate.to_Hz(),
+            self.baud_rounding,
+        )?;
+        Ok(HertzU32::from_raw(baudrate))
+    }
 }
 
 /// Rx/Tx FIFO Watermark
 ///
 /// Determine the FIFO level that trigger DMA/Interrupt
 /// Default is Bytes16, see DS Table 423 and UARTIFLS Register
+///
+/// The watermarks only apply when the FIFOs are enabled, see [`UartConfig::fifos`].
+///
 /// Example of use:
-///     uart0.set_fifos(true); // Default is false
 ///     uart0.set_rx_watermark(hal::uart::FifoWatermark::Bytes8);
 ///     uart0.enable_rx_interrupt();
 ///
@@ -163,6 +300,91 @@ pub enum FifoWatermark {
     Bytes28,
 }
 
+/// A set of UART interrupts.
+///
+/// Sets can be combined with `|`, eg. `UartInterrupts::RX | UartInterrupts::RX_TIMEOUT`.
+///
+/// See Chapter 4, Section 2 §8 - "UARTIMSC Register"
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct UartInterrupts(u16);
+
+impl UartInterrupts {
+    /// No interrupt.
+    pub const NONE: Self = Self(0);
+    /// The CTS line changed.
+    pub const CTS: Self = Self(1 << 1);
+    /// The RX FIFO reached its watermark.
+    pub const RX: Self = Self(1 << 4);
+    /// The TX FIFO went down to its watermark.
+    pub const TX: Self = Self(1 << 5);
+    /// The RX FIFO is not empty and no data was received for 32-bit periods.
+    pub const RX_TIMEOUT: Self = Self(1 << 6);
+    /// A character without a valid stop bit was received.
+    pub const FRAMING_ERROR: Self = Self(1 << 7);
+    /// A character with a parity mismatch was received.
+    pub const PARITY_ERROR: Self = Self(1 << 8);
+    /// A break was received.
+    pub const BREAK_ERROR: Self = Self(1 << 9);
+    /// Data was received while the RX FIFO was full.
+    pub const OVERRUN_ERROR: Self = Self(1 << 10);
+    /// All the receive error interrupts.
+    pub const RECEIVE_ERRORS: Self = Self(
+        Self::FRAMING_ERROR.0 | Self::PARITY_ERROR.0 | Self::BREAK_ERROR.0 | Self::OVERRUN_ERROR.0,
+    );
+
+    /// The interrupts related to the receive path, handled by the [`Reader`](super::Reader)
+    /// half of a split UART.
+    pub(crate) const RECEIVE: Self = Self(Self::RX.0 | Self::RX_TIMEOUT.0 | Self::RECEIVE_ERRORS.0);
+
+    const ALL: u16 =
+        Self::CTS.0 | Self::RX.0 | Self::TX.0 | Self::RX_TIMEOUT.0 | Self::RECEIVE_ERRORS.0;
+
+    /// The set of all the supported interrupts.
+    pub(crate) const fn all() -> Self {
+        Self(Self::ALL)
+    }
+
+    /// Builds a set from the raw register bits, ignoring the unsupported ones.
+    pub(crate) const fn from_bits(bits: u32) -> Self {
+        Self(bits as u16 & Self::ALL)
+    }
+
+    /// The raw register bits of the set.
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Is every interrupt of `other` also in this set?
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Is any interrupt of `other` also in this set?
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Is the set empty?
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for UartInterrupts {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for UartInterrupts {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
 impl Default for UartConfig {
     fn default() -> Self {
         Self {
@@ -170,6 +392,9 @@ impl Default for UartConfig {
             data_bits: DataBits::Eight,
             stop_bits: StopBits::One,
             parity: None,
+            baud_rounding: BaudRounding::Nearest,
+            fifos: true,
+            dma: false,
         }
     }
 }
@@ -26,16 +26,31 @@ pub fn set_tx_watermark(rb: &RegisterBlock, watermark: FifoWatermark) {
     rb.uartifls.modify(|_r, w| unsafe { w.txiflsel().bits(wm) });
 }
 
-/// Returns `Err(WouldBlock)` if the UART TX FIFO still has data in it or
-/// `Ok(())` if the FIFO is empty.
-pub(crate) fn transmit_flushed(rb: &RegisterBlock) -> nb::Result<(), Infallible> {
-    if rb.uartfr.read().txfe().bit_is_set() {
+/// Returns `Err(WouldBlock)` if the UART is still transmitting data or
+/// `Ok(())` once the TX FIFO is empty and the last stop bit has left the shift register.
+///
+/// While the transmitter (or the whole UART) is disabled, nothing is being sent and `Ok(())` is
+/// returned right away, even if bytes are waiting in the TX FIFO.
+pub(crate) fn transmit_idle(rb: &RegisterBlock) -> nb::Result<(), Infallible> {
+    if is_transmit_idle(rb.uartcr.read().bits(), rb.uartfr.read().bits()) {
         Ok(())
     } else {
         Err(WouldBlock)
     }
 }
 
+const UARTEN: u32 = 1 << 0;
+const TXE: u32 = 1 << 8;
+const BUSY: u32 = 1 << 3;
+
+/// Tells from the UARTCR and UARTFR values whether the transmitter is idle.
+///
+/// BUSY stays set as long as the TX FIFO isn't empty, even when the transmitter is disabled, so
+/// it only means something while TXE and UARTEN are set.
+fn is_transmit_idle(cr: u32, fr: u32) -> bool {
+    cr & (UARTEN | TXE) != UARTEN | TXE || fr & BUSY == 0
+}
+
 /// Returns `true` if the TX FIFO has space, or false if it is full
 pub(crate) fn uart_is_writable(rb: &RegisterBlock) -> bool {
     rb.uartfr.read().txff().bit_is_clear()
@@ -78,12 +93,38 @@ pub(crate) fn write_raw<'d>(
 ///
 /// This function blocks until the full buffer has been sent.
 pub(crate) fn write_full_blocking(rb: &RegisterBlock, data: &[u8]) {
+    write_full_blocking_with(rb, data, &mut || {})
+}
+
+/// Writes as many bytes as possible, advancing `data` past them.
+///
+/// Returns `Ok(())` once `data` is empty and `WouldBlock` as long as bytes remain.
+pub(crate) fn write_remaining(rb: &RegisterBlock, data: &mut &[u8]) -> nb::Result<(), Infallible> {
+    if let Ok(remaining) = write_raw(rb, data) {
+        *data = remaining;
+    }
+    if data.is_empty() {
+        Ok(())
+    } else {
+        Err(WouldBlock)
+    }
+}
+
+/// Writes bytes to the UART, calling `on_block` every time the TX FIFO is full.
+pub(crate) fn write_full_blocking_with(
+    rb: &RegisterBlock,
+    data: &[u8],
+    on_block: &mut impl FnMut(),
+) {
     let mut temp = data;
 
     while !temp.is_empty() {
         temp = match write_raw(rb, temp) {
             Ok(remaining) => remaining,
-            Err(WouldBlock) => continue,
+            Err(WouldBlock) => {
+                on_block();
+                continue;
+            }
             Err(_) => unreachable!(),
         }
     }
@@ -123,6 +164,11 @@ pub(crate) fn disable_tx_interrupt(rb: &RegisterBlock) {
     });
 }
 
+/// Enables/disables the TX DMA requests.
+pub(crate) fn set_dma(rb: &RegisterBlock, enable: bool) {
+    rb.uartdmacr.modify(|_r, w| w.txdmae().bit(enable));
+}
+
 /// Half of an [`UartPeripheral`] that is only capable of writing. Obtained by calling [`UartPeripheral::split()`]
 ///
 /// [`UartPeripheral`]: struct.UartPeripheral.html
@@ -146,6 +192,24 @@ impl<D: UartDevice, P: ValidUartPinout<D>> Writer<D, P> {
         write_raw(&self.device, data)
     }
 
+    /// Blocks until the transmitter is idle.
+    ///
+    /// See [`UartPeripheral::flush`].
+    ///
+    /// [`UartPeripheral::flush`]: super::UartPeripheral::flush
+    pub fn flush(&self) {
+        let _ = nb::block!(transmit_idle(&self.device));
+    }
+
+    /// Writes bytes to the UART, advancing `data` past the bytes written.
+    ///
+    /// See [`UartPeripheral::write_remaining`].
+    ///
+    /// [`UartPeripheral::write_remaining`]: super::UartPeripheral::write_remaining
+    pub fn write_remaining(&self, data: &mut &[u8]) -> nb::Result<(), Infallible> {
+        write_remaining(&self.device, data)
+    }
+
     /// Writes bytes to the UART.
     ///
     /// This function blocks until the full buffer has been sent.
@@ -153,6 +217,16 @@ impl<D: UartDevice, P: ValidUartPinout<D>> Writer<D, P> {
         write_full_blocking(&self.device, data);
     }
 
+    /// Writes bytes to the UART, calling `on_block` every time it has to wait for room in the
+    /// TX FIFO.
+    ///
+    /// See [`UartPeripheral::write_full_blocking_with`].
+    ///
+    /// [`UartPeripheral::write_full_blocking_with`]: super::UartPeripheral::write_full_blocking_with
+    pub fn write_full_blocking_with(&self, data: &[u8], on_block: &mut impl FnMut()) {
+        write_full_blocking_with(&self.device, data, on_block);
+    }
+
     /// Enables the Transmit Interrupt.
     ///
     /// The relevant UARTx IRQ will fire when there is space in the transmit FIFO.
@@ -178,7 +252,7 @@ impl<D: UartDevice, P: ValidUartPinout<D>> Write<u8> for Writer<D, P> {
     }
 
     fn flush(&mut self) -> nb::Result<(), Self::Error> {
-        transmit_flushed(&self.device)
+        transmit_idle(&self.device)
     }
 }
 
@@ -192,6 +266,7 @@ unsafe impl<D: UartDevice, P: ValidUartPinout<D>> WriteTarget for Writer<D, P> {
     }
 
     fn tx_address_count(&mut self) -> (u32, u32) {
+        set_dma(&self.device, true);
         (&self.device.uartdr as *const _ as u32, u32::MAX)
     }
 
@@ -218,7 +293,7 @@ impl<D: UartDevice, P: ValidUartPinout<D>> eh1nb::Write<u8> for Writer<D, P> {
     }
 
     fn flush(&mut self) -> nb::Result<(), Self::Error> {
-        transmit_flushed(&self.device).map_err(|e| match e {
+        transmit_idle(&self.device).map_err(|e| match e {
             WouldBlock => WouldBlock,
             Other(v) => match v {},
         })
@@ -227,8 +302,23 @@ impl<D: UartDevice, P: ValidUartPinout<D>> eh1nb::Write<u8> for Writer<D, P> {
 
 impl<D: UartDevice, P: ValidUartPinout<D>> fmt::Write for Writer<D, P> {
     fn write_str(&mut self, s: &str) -> fmt::Result {
-        s.bytes()
-            .try_for_each(|c| nb::block!(self.write(c)))
-            .map_err(|_| fmt::Error)
+        self.write_full_blocking(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_transmit_idle() {
+        // transmitter enabled: follows BUSY
+        assert!(is_transmit_idle(UARTEN | TXE, 0));
+        assert!(!is_transmit_idle(UARTEN | TXE, BUSY));
+        // transmitter or UART disabled: BUSY only means the FIFO isn't empty
+        assert!(is_transmit_idle(UARTEN, BUSY));
+        assert!(is_transmit_idle(TXE, BUSY));
+        assert!(is_transmit_idle(0, BUSY));
     }
 }
@@ -46,16 +46,33 @@ pub enum Error {
 
     /// Argument is bad : overflows, ...
     BadArgument,
+
+    /// The oscillator did not become stable in time (eg. no crystal is fitted)
+    ///
+    /// The wait is bounded by a number of polls, not by a duration: how long it takes depends
+    /// on the frequency `clk_sys` runs at.
+    Unstable,
 }
 
+/// How many times [`setup_xosc_blocking`] polls the oscillator before giving up.
+///
+/// This is a poll count rather than a duration, so the actual timeout scales with `clk_sys`.
+/// The startup delay is 1ms, this leaves a wide margin even when running from the ROSC.
+const STABILIZATION_POLLS: u32 = 100_000;
+
 /// Blocking helper method to setup the XOSC without going through all the steps.
+///
+/// Returns [`Error::Unstable`] instead of hanging if the oscillator never becomes stable
+/// within a fixed number of polls.
 pub fn setup_xosc_blocking(
     xosc_dev: XOSC,
     frequency: HertzU32,
 ) -> Result<CrystalOscillator<Stable>, Error> {
     let initialized_xosc = CrystalOscillator::new(xosc_dev).initialize(frequency)?;
 
-    let stable_xosc_token = nb::block!(initialized_xosc.await_stabilization()).unwrap();
+    let stable_xosc_token = (0..STABILIZATION_POLLS)
+        .find_map(|_| initialized_xosc.await_stabilization().ok())
+        .ok_or(Error::Unstable)?;
 
     Ok(initialized_xosc.get_stable(stable_xosc_token))
 }